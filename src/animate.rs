@@ -0,0 +1,323 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::aocbail;
+use crate::runner::{self, DayReport, RunOptions};
+use crate::utils::{AOCResult, Automaton, Dense, Glyph};
+
+// How often keys are polled while waiting for the next frame.
+const POLL: Duration = Duration::from_millis(10);
+
+// A simulation that can be drawn between steps. `step` returns false, without
+// changing anything, once there is nothing left to show.
+pub trait Animation: fmt::Display {
+    fn step(&mut self) -> bool;
+}
+
+impl<T: Clone + PartialEq + Glyph> Animation for Dense<T> {
+    fn step(&mut self) -> bool {
+        Automaton::step(self)
+    }
+}
+
+// Stops an endless animation after a fixed number of steps, such as the
+// puzzle's 6 boot cycles or 100 days.
+pub struct Take<A> {
+    animation: A,
+    steps: usize,
+}
+
+impl<A> Take<A> {
+    pub fn new(animation: A, steps: usize) -> Self {
+        Take { animation, steps }
+    }
+}
+
+impl<A: Animation> Animation for Take<A> {
+    fn step(&mut self) -> bool {
+        if self.steps == 0 {
+            return false;
+        }
+        self.steps -= 1;
+        self.animation.step()
+    }
+}
+
+impl<A: fmt::Display> fmt::Display for Take<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.animation, f)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnimateOptions {
+    pub delay: Duration,
+}
+
+impl Default for AnimateOptions {
+    fn default() -> Self {
+        AnimateOptions {
+            delay: Duration::from_millis(200),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Redraw,
+    Step,
+    Quit,
+}
+
+// Playback state, driven by the clock and by keys.
+#[derive(Debug, Default)]
+struct Player {
+    steps: usize,
+    paused: bool,
+    finished: bool,
+}
+
+impl Player {
+    fn key(&mut self, key: u8) -> Option<Action> {
+        match key {
+            b' ' | b'p' => {
+                self.paused = !self.paused;
+                Some(Action::Redraw)
+            }
+            b'n' | b'.' => {
+                self.paused = true;
+                Some(Action::Step)
+            }
+            // Ctrl-C arrives as a key, since signals are off while playing.
+            b'q' | 0x03 => Some(Action::Quit),
+            _ => None,
+        }
+    }
+
+    fn status(&self, title: &str, interactive: bool) -> String {
+        let state = if self.finished {
+            "finished"
+        } else if self.paused {
+            "paused"
+        } else {
+            "playing"
+        };
+        let mut status = format!("{} | step {} | {}", title, self.steps, state);
+        if interactive {
+            status.push_str(" | space: pause, n: step, q: quit");
+        }
+        status
+    }
+}
+
+// The top left `rows` x `cols` of a frame.
+fn clip(frame: &str, rows: usize, cols: usize) -> Vec<&str> {
+    frame
+        .lines()
+        .take(rows)
+        .map(|line| match line.char_indices().nth(cols) {
+            Some((end, _)) => &line[..end],
+            None => line,
+        })
+        .collect()
+}
+
+// On a `(rows, columns)` screen, redraws in place and keeps the last row
+// for `status`. Otherwise the frame and its status are written out plainly,
+// followed by a blank line, so redirected output stays readable.
+fn render(frame: &str, status: &str, screen: Option<(usize, usize)>) -> String {
+    let (rows, cols) = match screen {
+        Some(screen) => screen,
+        None => return format!("{}\n{}\n\n", frame.trim_end_matches('\n'), status),
+    };
+    let mut out = String::from("\x1b[H");
+    for line in clip(frame, rows.saturating_sub(1), cols) {
+        out.push_str(line);
+        out.push_str("\x1b[K\n");
+    }
+    out.extend(clip(status, 1, cols));
+    out.push_str("\x1b[K\x1b[J");
+    out
+}
+
+// Runs `stty` against the controlling terminal, returning what it printed.
+fn stty(tty: &File, args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::from(tty.try_clone().ok()?))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        None
+    }
+}
+
+// The controlling terminal, switched to unbuffered, silent keys for as long
+// as this is alive. Without one (no /dev/tty, no stty, or stdout going
+// somewhere else) frames are written out one after another and nothing can
+// be paused or stepped.
+struct Terminal {
+    tty: Option<File>,
+    saved: Option<String>,
+}
+
+impl Terminal {
+    fn open() -> Terminal {
+        // Drawing in place only makes sense when stdout is the screen.
+        let tty = File::open("/dev/tty").ok().filter(|_| std::io::stdout().is_terminal());
+        let saved = tty.as_ref().and_then(|tty| stty(tty, &["-g"]));
+        let terminal = Terminal { tty, saved };
+        if let (Some(tty), Some(_)) = (&terminal.tty, &terminal.saved) {
+            // No signals either, so Ctrl-C quits through Drop and the
+            // terminal is always put back.
+            stty(tty, &["-icanon", "-echo", "-isig", "min", "0", "time", "0"]);
+            // Alternate screen, hidden cursor.
+            print!("\x1b[?1049h\x1b[?25l");
+        }
+        terminal
+    }
+
+    fn interactive(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    // (rows, columns), falling back to $LINES and $COLUMNS, then 24x80.
+    fn size(&self) -> (usize, usize) {
+        let queried = self.tty.as_ref().and_then(|tty| stty(tty, &["size"])).and_then(|size| {
+            let (rows, cols) = size.split_once(' ')?;
+            Some((rows.parse().ok()?, cols.parse().ok()?))
+        });
+        queried.unwrap_or_else(|| {
+            let var = |name, default| std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+            (var("LINES", 24), var("COLUMNS", 80))
+        })
+    }
+
+    fn key(&mut self) -> AOCResult<Option<u8>> {
+        let mut key = [0];
+        match &mut self.tty {
+            Some(tty) if self.saved.is_some() => Ok(if tty.read(&mut key)? == 1 { Some(key[0]) } else { None }),
+            _ => Ok(None),
+        }
+    }
+
+    fn draw(&self, frame: &str, status: &str) -> AOCResult<()> {
+        let screen = if self.interactive() { Some(self.size()) } else { None };
+        let out = render(frame, status, screen);
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if let (Some(tty), Some(saved)) = (&self.tty, &self.saved) {
+            print!("\x1b[?25h\x1b[?1049l");
+            let _ = std::io::stdout().flush();
+            stty(tty, &[saved]);
+        }
+    }
+}
+
+// Draws `animation` after every step, `options.delay` apart, until it
+// finishes. On a terminal the last frame stays up until 'q' is pressed.
+pub fn play(title: &str, animation: &mut dyn Animation, options: &AnimateOptions) -> AOCResult<()> {
+    let mut terminal = Terminal::open();
+    let mut player = Player::default();
+    loop {
+        terminal.draw(&animation.to_string(), &player.status(title, terminal.interactive()))?;
+        if player.finished && !terminal.interactive() {
+            return Ok(());
+        }
+
+        let deadline = Instant::now() + options.delay;
+        let advance = loop {
+            match terminal.key()?.and_then(|key| player.key(key)) {
+                Some(Action::Quit) => return Ok(()),
+                Some(Action::Step) => break true,
+                Some(Action::Redraw) => break false,
+                None if !player.paused && !player.finished && Instant::now() >= deadline => break true,
+                None => thread::sleep(POLL),
+            }
+        };
+        if advance && !player.finished {
+            if animation.step() {
+                player.steps += 1;
+            } else {
+                player.finished = true;
+            }
+        }
+    }
+}
+
+// Plays every requested part of `day` that has an animation, then prints
+// the answers as `run` would.
+pub fn run(day: u8, options: &RunOptions, animate: &AnimateOptions) -> AOCResult<DayReport> {
+    let solution = runner::solution(day)?;
+    let model = solution.parse(&options.input(solution))?;
+    let animations = options
+        .parts()
+        .into_iter()
+        .filter_map(|part| Some((part, solution.animate(part, model.as_ref())?)))
+        .collect::<Vec<_>>();
+    if animations.is_empty() {
+        aocbail!("{} has nothing to animate", solution.name());
+    }
+
+    for (part, mut animation) in animations {
+        play(&format!("{} part {}", solution.name(), part), animation.as_mut(), animate)?;
+    }
+    runner::run_day(day, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animate::*;
+
+    struct Counter(usize);
+
+    impl Animation for Counter {
+        fn step(&mut self) -> bool {
+            self.0 += 1;
+            true
+        }
+    }
+
+    impl fmt::Display for Counter {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[test]
+    pub fn test_animate() {
+        assert_eq!(clip("abc\ndé\nghi\n", 2, 2), vec!["ab", "dé"]);
+        assert_eq!(clip("abc", 0, 2), Vec::<&str>::new());
+        assert_eq!(render("ab\ncd\n", "step 1", None), "ab\ncd\nstep 1\n\n");
+        assert_eq!(render("abc\nd\n", "step 1", Some((2, 2))), "\x1b[Hab\x1b[K\nst\x1b[K\x1b[J");
+
+        let mut counter = Take::new(Counter(0), 2);
+        assert!(counter.step() && counter.step());
+        assert!(!counter.step());
+        assert_eq!(counter.to_string(), "2");
+
+        let mut player = Player::default();
+        assert_eq!(player.key(b' '), Some(Action::Redraw));
+        assert!(player.paused);
+        assert_eq!(player.key(b'n'), Some(Action::Step));
+        assert!(player.paused);
+        assert_eq!(player.key(b'q'), Some(Action::Quit));
+        assert_eq!(player.key(0x03), Some(Action::Quit));
+        assert_eq!(player.key(b'x'), None);
+        assert_eq!(player.status("day", false), "day | step 0 | paused");
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::aocbail;
+use crate::report::{self, Record};
+use crate::runner::{self, DayReport, Failure, RunOptions};
+use crate::solution::Day;
+use crate::utils::{self, AOCResult, Input};
+
+// Every requested day solved on every profile's input, reports[profile][day].
+pub struct Batch {
+    pub profiles: Vec<String>,
+    pub parts: Vec<u8>,
+    pub reports: Vec<Vec<DayReport>>,
+}
+
+// Solves `days` on `<dir>/<profile>/dayN` for each profile, spread over
+// `jobs` threads.
+pub fn run(dir: &Path, days: &[u8], profiles: &[String], options: &RunOptions, jobs: usize) -> AOCResult<Batch> {
+    if profiles.is_empty() {
+        aocbail!("No input profiles in {}, expected one directory of dayN files per person", dir.display());
+    }
+    let solutions = days.iter().map(|day| runner::solution(*day)).collect::<AOCResult<Vec<_>>>()?;
+    let work = profiles
+        .iter()
+        .flat_map(|profile| solutions.iter().map(move |solution| (profile, *solution)))
+        .collect::<Vec<_>>();
+    let input = |profile: &str, solution: &dyn Day| Input::Path(dir.join(profile).join(format!("day{}", solution.day())));
+    let reports = utils::map_parallel(&work, jobs, |(profile, solution)| {
+        runner::solve(*solution, &RunOptions { input: Some(input(profile, *solution)), ..options.clone() })
+    });
+
+    let mut reports = work
+        .iter()
+        .zip(reports)
+        .map(|((profile, solution), report)| report.unwrap_or_else(|| DayReport::panicked(*solution, input(profile, *solution))));
+    let reports = profiles.iter().map(|_| reports.by_ref().take(days.len()).collect()).collect();
+    Ok(Batch {
+        profiles: profiles.to_vec(),
+        parts: options.parts(),
+        reports,
+    })
+}
+
+fn cell(report: &DayReport, part: u8) -> (bool, String) {
+    if let Err(failure) = &report.parsed {
+        return (false, format!("parse {}", failure));
+    }
+    match report.parts.iter().find(|p| p.part == part).map(|p| &p.answer) {
+        Some(Ok(answer)) => (true, answer.to_string()),
+        Some(Err(failure)) => (false, failure.to_string()),
+        None => (false, Failure::NotRun.to_string()),
+    }
+}
+
+impl Batch {
+    // One row per day and part, one column of answers per profile. Rows
+    // where some profiles failed and others didn't are marked with a '!'.
+    pub fn table(&self) -> String {
+        let header = ["day", "name", "part"].iter().map(|s| s.to_string());
+        let mut rows = vec![header.chain(self.profiles.iter().cloned()).collect::<Vec<_>>()];
+        let mut marks = vec![""];
+        for (i, first) in self.reports.first().into_iter().flatten().enumerate() {
+            for part in self.parts.iter().copied() {
+                let cells = self.reports.iter().map(|reports| cell(&reports[i], part)).collect::<Vec<_>>();
+                let ok = cells.iter().filter(|(ok, _)| *ok).count();
+                marks.push(if ok > 0 && ok < cells.len() { "!" } else { "" });
+                let mut row = vec![first.day.to_string(), first.name.to_owned(), part.to_string()];
+                row.extend(cells.into_iter().map(|(_, text)| text));
+                rows.push(row);
+            }
+        }
+
+        let columns = rows[0].len();
+        let widths = (0..columns).map(|c| rows.iter().map(|row| row[c].chars().count()).max().unwrap_or(0)).collect::<Vec<_>>();
+        let mut out = String::new();
+        for (row, mark) in rows.iter().zip(marks) {
+            let mut line = format!("{:1}{:>3}  {:<w1$}  {:>4}", mark, row[0], row[1], row[2], w1 = widths[1]);
+            for (text, width) in row.iter().zip(widths.iter()).skip(3) {
+                let _ = write!(line, "  {:<width$}", text, width = width);
+            }
+            let _ = writeln!(out, "{}", line.trim_end());
+        }
+        out
+    }
+
+    // Day/part rows that failed on at least one profile.
+    pub fn failed(&self) -> usize {
+        let days = self.reports.first().map_or(0, Vec::len);
+        (0..days)
+            .flat_map(|i| self.parts.iter().map(move |part| (i, *part)))
+            .filter(|(i, part)| self.reports.iter().any(|reports| !cell(&reports[*i], *part).0))
+            .count()
+    }
+
+    // Records for --format json or csv, grouped by profile; each record's
+    // input says which profile it came from.
+    pub fn records(&self) -> Vec<Record> {
+        self.reports.iter().flat_map(|reports| report::records(reports, &self.parts)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::*;
+
+    #[test]
+    pub fn test_batch() {
+        let dir = utils::test_dir("batch");
+        for profile in ["alice", "bob", "carol"].iter() {
+            std::fs::create_dir_all(dir.join(profile)).unwrap();
+        }
+        std::fs::write(dir.join("alice/day8"), utils::get_input("test_day8").unwrap().collect::<Vec<_>>().join("\n")).unwrap();
+        std::fs::write(dir.join("bob/day8"), "acc +1\njmp +0\njmp -1\n").unwrap();
+        std::fs::write(dir.join("carol/notes"), "not an input").unwrap();
+
+        let profiles = utils::profiles(&dir).unwrap();
+        assert_eq!(profiles, vec!["alice", "bob"]);
+        let batch = run(&dir, &[8], &profiles, &RunOptions::default(), 2).unwrap();
+        let table = batch.table();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], " day  name              part  alice  bob");
+        assert_eq!(lines[1], "   8  handheld_halting     1  5      1");
+        assert!(lines[2].starts_with("!  8  handheld_halting     2  8      error: "));
+        assert_eq!(batch.failed(), 1);
+        assert_eq!(batch.records().len(), 4);
+
+        assert!(run(&dir, &[8], &[], &RunOptions::default(), 1).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::runner::{self, RunOptions};
+use crate::solution::Day;
+use crate::report;
+use crate::utils::{self, format_duration, AOCResult, Allocs, Input, OptionExt};
+use crate::aocbail;
+
+pub struct BenchOptions {
+    pub runs: usize,
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    // Fraction a median may grow by before it counts as a regression.
+    pub threshold: f64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            runs: 5,
+            save: None,
+            baseline: None,
+            threshold: 0.1,
+        }
+    }
+}
+
+pub struct Timing {
+    pub day: u8,
+    pub phase: &'static str,
+    pub samples: Vec<Duration>,
+    // What the last run allocated, with the count-allocs feature.
+    pub allocs: Option<Allocs>,
+}
+
+impl Timing {
+    fn new(day: u8, phase: &'static str) -> Self {
+        Timing {
+            day,
+            phase,
+            samples: Vec::new(),
+            allocs: None,
+        }
+    }
+
+    fn time<T>(&mut self, f: impl FnOnce() -> AOCResult<T>) -> AOCResult<T> {
+        let start = Instant::now();
+        let (allocs, result) = utils::measure(f);
+        self.samples.push(start.elapsed());
+        self.allocs = Some(allocs).filter(|_| utils::counting());
+        result
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.iter().max().copied().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut samples = self.samples.clone();
+        samples.sort_unstable();
+        samples.get(samples.len() / 2).copied().unwrap_or_default()
+    }
+}
+
+// Parses the input and runs each requested part `runs` times, keeping
+// every sample so the caller can pick whichever statistic it wants.
+pub fn bench_day(
+    solution: &dyn Day,
+    input: &Input,
+    parts: &[u8],
+    runs: usize,
+) -> AOCResult<Vec<Timing>> {
+    let mut parse = Timing::new(solution.day(), "parse");
+    let mut model = None;
+    for _ in 0..runs {
+        model = Some(parse.time(|| solution.parse(input))?);
+    }
+    let model = model.ok_or_none()?;
+
+    let mut timings = vec![parse];
+    for part in parts {
+        let mut timing = Timing::new(solution.day(), if *part == 1 { "part1" } else { "part2" });
+        for _ in 0..runs {
+            timing.time(|| solution.part(*part, model.as_ref()))?;
+        }
+        timings.push(timing);
+    }
+    Ok(timings)
+}
+
+// Baselines are plain text, one "<day> <phase> <median in ns>" per line.
+pub fn load_baseline(path: &Path) -> AOCResult<HashMap<(u8, String), Duration>> {
+    let mut baseline = HashMap::new();
+    for line in std::fs::read_to_string(path)?.lines() {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 3 {
+            aocbail!("Malformed baseline entry '{}' in {}", line, path.display());
+        }
+        baseline.insert(
+            (fields[0].parse::<u8>()?, fields[1].to_owned()),
+            Duration::from_nanos(fields[2].parse::<u64>()?),
+        );
+    }
+    Ok(baseline)
+}
+
+pub fn save_baseline(path: &Path, timings: &[Timing]) -> AOCResult<()> {
+    let mut contents = String::new();
+    for timing in timings {
+        writeln!(contents, "{} {} {}", timing.day, timing.phase, timing.median().as_nanos())?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+// Relative change of the median against a baseline median, if there is one.
+fn change(timing: &Timing, baseline: Option<&Duration>) -> Option<f64> {
+    baseline
+        .filter(|previous| !previous.is_zero())
+        .map(|previous| timing.median().as_secs_f64() / previous.as_secs_f64() - 1.0)
+}
+
+pub fn run(days: &[u8], options: &RunOptions, bench: &BenchOptions) -> AOCResult<()> {
+    let baseline = match &bench.baseline {
+        Some(path) => load_baseline(path)?,
+        None => HashMap::new(),
+    };
+
+    let counted = utils::counting();
+    let allocs = |allocs| if counted { report::alloc_columns(allocs) } else { String::new() };
+    let header = if counted { report::alloc_header() } else { String::new() };
+    println!(
+        "{:>3}  {:<6} {:>10} {:>10} {:>10}{}",
+        "day", "phase", "min", "median", "max", header
+    );
+    let mut timings = Vec::new();
+    let mut regressions = 0;
+    for day in days {
+        let solution = runner::solution(*day)?;
+        let input = options.input(solution);
+        for timing in bench_day(solution, &input, &options.parts(), bench.runs)? {
+            let note = match change(&timing, baseline.get(&(timing.day, timing.phase.to_owned()))) {
+                Some(c) if c > bench.threshold => {
+                    regressions += 1;
+                    format!("{:>+8.1}%  REGRESSION", c * 100.0)
+                }
+                Some(c) => format!("{:>+8.1}%", c * 100.0),
+                None => String::new(),
+            };
+            println!(
+                "{:>3}  {:<6} {:>10} {:>10} {:>10}{}{}",
+                timing.day,
+                timing.phase,
+                format_duration(timing.min()),
+                format_duration(timing.median()),
+                format_duration(timing.max()),
+                allocs(timing.allocs),
+                note,
+            );
+            timings.push(timing);
+        }
+    }
+
+    if let Some(path) = &bench.save {
+        save_baseline(path, &timings)?;
+        println!("Saved baseline to {}", path.display());
+    }
+    if regressions > 0 {
+        aocbail!("{} phase(s) regressed by more than {:.0}%", regressions, bench.threshold * 100.0);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::*;
+
+    #[test]
+    pub fn test_bench_baseline() {
+        let solution = runner::solution(6).unwrap();
+        let input = Input::from("test_day6");
+        let timings = bench_day(solution, &input, &[1, 2], 3).unwrap();
+        assert_eq!(
+            timings.iter().map(|t| t.phase).collect::<Vec<_>>(),
+            vec!["parse", "part1", "part2"]
+        );
+        assert!(timings.iter().all(|t| t.samples.len() == 3));
+        assert!(timings[0].min() <= timings[0].median() && timings[0].median() <= timings[0].max());
+
+        let dir = utils::test_dir("bench");
+        let path = dir.join("baseline");
+        save_baseline(&path, &timings).unwrap();
+        let baseline = load_baseline(&path).unwrap();
+        assert_eq!(baseline.len(), 3);
+        assert_eq!(baseline[&(6, "part2".to_owned())].as_nanos(), timings[2].median().as_nanos());
+    }
+}
@@ -1,24 +1,39 @@
-#![allow(dead_code)]
-
-use crate::{utils};
+use crate::{solution::Solution, utils};
+use utils::{AOCResult, Input};
 use std::collections::HashMap;
 
-pub fn day10() {
-    let adaptors = load("day10");
-    println!("adaptor_array part 1: {:?}", count_deltas(&adaptors));
-    println!("adaptor_array part 2: {:?}", count_arrangements(&adaptors));
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Model = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u64;
+
+    const DAY: u8 = 10;
+    const NAME: &'static str = "adaptor_array";
+
+    fn parse(input: &Input) -> AOCResult<Vec<u32>> {
+        load(input)
+    }
+
+    fn part1(adaptors: &Vec<u32>) -> AOCResult<u32> {
+        Ok(count_deltas(adaptors))
+    }
+
+    fn part2(adaptors: &Vec<u32>) -> AOCResult<u64> {
+        Ok(count_arrangements(adaptors))
+    }
 }
 
-pub fn load(filename: &str) -> Vec<u32> {
-    let input = utils::get_input(filename);
-    let mut adaptors = input.map(|i| i.parse::<u32>().unwrap() ).collect::<Vec<u32>>();
+pub fn load(input: impl Into<Input>) -> AOCResult<Vec<u32>> {
+    let mut adaptors = utils::get_input(input)?.parse_each(|p| p.int::<u32>())?;
     adaptors.insert(0, 0);
     adaptors.sort_unstable();
     adaptors.push(adaptors[adaptors.len()-1] + 3);
-    adaptors 
+    Ok(adaptors)
 }
 
-pub fn count_deltas(adaptors: &Vec<u32>) -> u32 {
+pub fn count_deltas(adaptors: &[u32]) -> u32 {
     let mut count_1volt = 0;
     let mut count_3volt = 0;
     for i in 1..adaptors.len() {
@@ -60,7 +75,7 @@ fn re_count_arrangements(adaptors: &Vec<u32>, from_index: usize, cache: &mut Has
 
 #[test]
 pub fn test_day10() {
-    let input = load("test_day10");
+    let input = load("test_day10").unwrap();
     assert_eq!(
         count_deltas(&input),
         35,
@@ -1,6 +1,6 @@
-#![allow(dead_code)]
-
-use crate::{utils};
+use crate::{animate::Animation, solution::Solution, utils};
+use std::fmt;
+use utils::{AOCResult, Automaton, Dense, Glyph, Grid2D, Input};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SeatState {
@@ -9,139 +9,124 @@ pub enum SeatState {
     Occupied,
 }
 
-type SeatStepMap = Vec<Vec<Vec<Option<(usize, usize)>>>>;
+impl Glyph for SeatState {
+    fn glyph(&self) -> char {
+        match self {
+            SeatState::Floor => '.',
+            SeatState::Empty => 'L',
+            SeatState::Occupied => '#',
+        }
+    }
+}
 
+#[derive(Clone)]
 pub struct SeatGrid {
-    grid: Vec<Vec<SeatState>>,
-    num_rows: usize,
-    num_columns: usize,
+    grid: Grid2D<SeatState>,
 }
 
 impl SeatGrid {
-    pub fn load(filename: &str) -> SeatGrid {
-        let input = utils::get_input(filename);
-        let mut grid: Vec<Vec<SeatState>> = Vec::new();
-
-        for line in input {
-            grid.push(line.chars().map(|seat| 
-                match seat {
-                    'L' => SeatState::Empty,
-                    '#' => SeatState::Occupied,
-                    _ => SeatState::Floor,
-                }).collect()
-            );
-        }
+    pub fn load(input: impl Into<Input>) -> AOCResult<SeatGrid> {
+        let grid = Grid2D::parse(&mut utils::get_input(input)?, |seat| match seat {
+            'L' => Some(SeatState::Empty),
+            '#' => Some(SeatState::Occupied),
+            '.' => Some(SeatState::Floor),
+            _ => None,
+        })?;
+        Ok(SeatGrid { grid })
+    }
 
-        SeatGrid {
-            num_columns: grid[0].len(),
-            num_rows: grid.len(),
-            grid
-        }
-   }
-
-   pub fn stabilize_immediate(&mut self) -> usize {
-       let mut step_map: SeatStepMap = Vec::new();
-
-       for (y, row) in self.grid.iter().enumerate() {
-           let mut new_row = Vec::new();
-           for (x, _seat) in row.iter().enumerate() {
-            new_row.push([
-                (-1, -1),
-                (-1, 0),
-                (-1, 1),
-                (0, -1),
-                (0, 1),
-                (1, -1),
-                (1, 0),
-                (1, 1)
-            ].iter().map(|(dx, dy)| self.in_grid(
-                dx + x as i32,
-                dy + y as i32,
-            )).collect());
-           }
-           step_map.push(new_row);
-       }
-
-       self.stabilize(step_map)
-   }
-
-    fn stabilize(&mut self, step_map: SeatStepMap) -> usize {
-        let mut cache = self.grid.clone();
-        loop {
-            let mut did_update = false;
-            let mut occupied_count = 0;
-
-            for (y, row) in self.grid.iter().enumerate() {
-                for (x, seat) in row.iter().enumerate() {
-                    let (num_occupied, _num_empty) = step_map[x][y].iter().map(|cell|
-                        match cell {
-                            Some((i, j)) => self.grid[*i][*j],
-                            None => SeatState::Floor,
-                        }
-                    ).fold((0, 0), |(ocount, ecount), seat| match seat {
-                        SeatState::Empty => (ocount, ecount+1),
-                        SeatState::Occupied => (ocount+1, ecount),
-                        SeatState::Floor => (ocount, ecount),
-                    });
-
-                    cache[y][x] = match seat {
-                        SeatState::Empty => if num_occupied == 0 { SeatState::Occupied } else { SeatState::Empty },
-                        SeatState::Occupied => if num_occupied >= 4 {SeatState::Empty } else { SeatState::Occupied },
-                        SeatState::Floor => SeatState::Floor,
-                    };
-
-                    if cache[y][x] == SeatState::Occupied {
-                        occupied_count += 1;
-                    }
-
-                    did_update = did_update || self.grid[y][x] != cache[y][x];
-                }
-            }
-
-            if did_update {
-                self.grid = cache.clone();
-            } else {
-                return occupied_count;
-            }
-        }
+    // The seating rules, with every seat watching the 8 around it.
+    fn immediate(&self) -> Dense<SeatState> {
+        Dense::new(
+            self.grid.clone(),
+            |grid, position| grid.neighbours8(position).collect(),
+            |seat| *seat == SeatState::Occupied,
+            |seat, occupied| match seat {
+                SeatState::Empty if occupied == 0 => SeatState::Occupied,
+                SeatState::Occupied if occupied >= 4 => SeatState::Empty,
+                seat => *seat,
+            },
+        )
     }
 
-    pub fn in_grid(&self, x: i32, y: i32) -> Option<(usize, usize)> {
-        if x < 0 || y < 0 || x >= self.num_columns as i32 || y >= self.num_rows as i32 {
-            None
+    // Runs the seating rules until nobody moves and counts who is left sitting.
+    pub fn stabilize_immediate(&mut self) -> usize {
+        let mut seats = self.immediate();
+        seats.run_until_stable();
+        let occupied = seats.count_live();
+        self.grid = seats.into_cells();
+        occupied
+    }
+
+    pub fn find_next_seat(&self, cell: (usize, usize), delta: (isize, isize)) -> Option<(usize, usize)> {
+        self.grid.cast(cell, delta, |seat| *seat != SeatState::Floor)
+    }
+}
+
+impl fmt::Display for SeatGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.grid, f)
+    }
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Model = SeatGrid;
+    type Part1 = usize;
+    type Part2 = ();
+
+    const DAY: u8 = 11;
+    const NAME: &'static str = "seating_system";
+
+    fn parse(input: &Input) -> AOCResult<SeatGrid> {
+        SeatGrid::load(input)
+    }
+
+    fn part1(grid: &SeatGrid) -> AOCResult<usize> {
+        Ok(grid.clone().stabilize_immediate())
+    }
+
+    fn part2(_grid: &SeatGrid) -> AOCResult<()> {
+        Ok(())
+    }
+
+    fn animate(grid: &SeatGrid, part: u8) -> Option<Box<dyn Animation>> {
+        if part == 1 {
+            Some(Box::new(grid.immediate()))
         } else {
-            Some((x as usize, y as usize))
+            None
         }
     }
 
-    pub fn find_next_seat(&self, cell: (i32, i32), delta: (i32, i32)) -> Option<(usize, usize)> {
-        let mut candidate = (cell.0 + delta.0, cell.1 + delta.1);
-        loop {
-            let cell = self.in_grid(candidate.0, candidate.1);
-            if let Some((x, y)) = cell {
-                if self.grid[x][y] != SeatState::Floor {
-                    break cell;
-                }
-                candidate.0 += delta.0;
-                candidate.1 += delta.1;
-            } else {
-                break None;
-            }
+    // Where everyone ends up sitting.
+    fn picture(grid: &SeatGrid, part: u8) -> AOCResult<Option<String>> {
+        if part != 1 {
+            return Ok(None);
         }
+        let mut grid = grid.clone();
+        grid.stabilize_immediate();
+        Ok(Some(grid.to_string()))
     }
 }
 
-pub fn day11() {
-    let mut grid = SeatGrid::load("day11");
-    println!("seating_system part 1: {:?}", grid.stabilize_immediate());
-    //println!("adaptor_array part 2: {:?}", count_arrangements(&adaptors));
-}
-
 #[test]
 pub fn test_day11() {
-    let mut input = SeatGrid::load("test_day11");
+    let mut input = SeatGrid::load("test_day11").unwrap();
     assert_eq!(
         input.stabilize_immediate(),
         37,
     );
 }
+
+#[test]
+pub fn test_day11_next_seat() {
+    let grid = SeatGrid::load("test_day11").unwrap();
+    assert_eq!(grid.find_next_seat((0, 0), (1, 0)), Some((2, 0)));
+}
+
+#[test]
+pub fn test_day11_display() {
+    let grid = SeatGrid::load("test_day11").unwrap();
+    assert!(grid.to_string().starts_with("L.LL.LL.LL\n"));
+}
@@ -1,39 +1,44 @@
-#![allow(dead_code)]
-
-use crate::{regex, utils};
-use utils::{AOCResult};
+use crate::{solution::Solution, utils};
+use utils::{AOCResult, Input, SpanExt};
 use strum_macros::EnumString;
 use std::str::FromStr;
-use lazy_static::lazy_static;
-use regex::Regex;
-
-lazy_static! {
-    static ref COMMAND_REGEX: Regex = regex!("^([A-Z])([0-9]+)$");
-}
 
 #[derive(EnumString)]
 pub enum Command {
     N, S, E, W, L, R, F
 }
 
-type CommandList = Vec<(Command, i32)>;
+pub type CommandList = Vec<(Command, i32)>;
 
-pub fn load(filename: &str) -> AOCResult<CommandList> {
-    let input = utils::get_input(filename);
-    let mut commands: CommandList = Vec::new();
-    for line in input {
-        let capture = COMMAND_REGEX.captures_iter(&line).next()?;
-        let command = Command::from_str(&capture[1])?;
-        let value = capture[2].parse::<i32>()?;
-        commands.push((command, value));
-    }
-    Ok(commands)
+pub fn load(input: impl Into<Input>) -> AOCResult<CommandList> {
+    utils::get_input(input)?.parse_each(|p| {
+        let command = p.take_while(|c| c.is_ascii_uppercase());
+        let command = Command::from_str(command).at(|| p.span(command))?;
+        Ok((command, p.int()?))
+    })
 }
 
-pub fn day12() {
-    let commands = load("day12").unwrap();
-    println!("rain_risk part 1: {:?}", manhattan_distance(&commands));
-    println!("rain_risk part 2: {:?}", waypoint_manhattan(&commands));
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Model = CommandList;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const DAY: u8 = 12;
+    const NAME: &'static str = "rain_risk";
+
+    fn parse(input: &Input) -> AOCResult<CommandList> {
+        load(input)
+    }
+
+    fn part1(commands: &CommandList) -> AOCResult<i32> {
+        Ok(manhattan_distance(commands))
+    }
+
+    fn part2(commands: &CommandList) -> AOCResult<i32> {
+        Ok(waypoint_manhattan(commands))
+    }
 }
 
 pub fn waypoint_manhattan(commands: &CommandList) -> i32 {
@@ -50,7 +55,7 @@ pub fn waypoint_manhattan(commands: &CommandList) -> i32 {
                 let steps = value / 90;
                 for _ in 0..steps {
                     waypoint = (
-                        -1 * waypoint.1,
+                        -waypoint.1,
                         waypoint.0,
                     );
                 }
@@ -60,7 +65,7 @@ pub fn waypoint_manhattan(commands: &CommandList) -> i32 {
                 for _ in 0..steps {
                     waypoint = (
                         waypoint.1,
-                        -1 * waypoint.0,
+                        -waypoint.0,
                     );
                 }
             },
@@ -76,7 +81,7 @@ pub fn waypoint_manhattan(commands: &CommandList) -> i32 {
 }
 
 pub fn manhattan_distance(commands: &CommandList) -> i32 {
-    let directions = vec![
+    let directions = [
         (Command::E, (1, 0)),
         (Command::S, (0, -1)),
         (Command::W, (-1, 0)),
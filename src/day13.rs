@@ -1,27 +1,50 @@
-#![allow(dead_code)]
+use crate::{aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input};
 
-use crate::{aocbail, utils};
-use utils::{AOCResult, AOCError};
+// The earliest departure time and the bus ids, None where the notes say 'x'.
+pub struct Notes {
+    departure: u32,
+    buses: Vec<Option<u32>>,
+}
 
-pub fn day13() {
-    let input = utils::get_input("day13");
-    println!("shuttle_search part 1: {:?}", find_schedule(input).unwrap());
-    let input = utils::get_input("day13");
-    println!("shuttle_search part 2: {:?}", chinese_remainder_theorem(input).unwrap());
+impl Notes {
+    pub fn load(input: impl Into<Input>) -> AOCResult<Notes> {
+        let mut input = utils::get_input(input)?;
+        let departure = input.parse_next(|p| p.int())?;
+        let buses = input.parse_next(|p| p.list(",", |p| if p.eat("x") { Ok(None) } else { p.int().map(Some) }))?;
+        Ok(Notes { departure, buses })
+    }
 }
 
-pub fn find_schedule(mut input: impl Iterator<Item = String>) -> AOCResult<u32> {
-    let departure = input.next()?.parse::<u32>()?;
-    let raw_schedules = input.next()?;
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Model = Notes;
+    type Part1 = u32;
+    type Part2 = u64;
+
+    const DAY: u8 = 13;
+    const NAME: &'static str = "shuttle_search";
 
+    fn parse(input: &Input) -> AOCResult<Notes> {
+        Notes::load(input)
+    }
+
+    fn part1(notes: &Notes) -> AOCResult<u32> {
+        find_schedule(notes)
+    }
+
+    fn part2(notes: &Notes) -> AOCResult<u64> {
+        chinese_remainder_theorem(notes)
+    }
+}
+
+pub fn find_schedule(notes: &Notes) -> AOCResult<u32> {
+    let departure = notes.departure;
     let mut min_id = 0;
     let mut min_wait_time = 0;
 
-    for raw_schedule in raw_schedules.split(",") {
-        if raw_schedule == "x" {
-            continue;
-        }
-        let schedule_id = raw_schedule.parse::<u32>()?;
+    for schedule_id in notes.buses.iter().flatten().copied() {
         let wait_time = ((departure / schedule_id) + 1) * schedule_id - departure;
 
         if min_id == 0 || wait_time < min_wait_time {
@@ -39,20 +62,17 @@ pub fn mod_inverse(a: u64, m: u64) -> AOCResult<u64> {
             return Ok(x);
         }
     }
-    println!("failed for {} {}", a, m);
-    aocbail!("mod_inverse failed!");
+    aocbail!("mod_inverse failed for {} {}", a, m);
 }
 
-pub fn chinese_remainder_theorem(mut input: impl Iterator<Item = String>) -> AOCResult<u64> {
-    let _ = input.next()?;
-    let raw_schedules = input.next()?;
+pub fn chinese_remainder_theorem(notes: &Notes) -> AOCResult<u64> {
     let mut buses = Vec::new();
     let mut big_n = 1;
-    for (i, raw_schedule) in raw_schedules.split(",").enumerate() {
-        if raw_schedule == "x" {
-            continue;
-        }
-        let bus = raw_schedule.parse::<i64>()?;
+    for (i, bus) in notes.buses.iter().enumerate() {
+        let bus = match bus {
+            Some(bus) => *bus as i64,
+            None => continue,
+        };
         let mut offset = bus - i as i64;
         while offset < 0  {
             offset += bus;
@@ -72,10 +92,10 @@ pub fn chinese_remainder_theorem(mut input: impl Iterator<Item = String>) -> AOC
 
 #[test]
 pub fn test_day13() {
-    let input = utils::get_input("test_day13");
-    assert_eq!(find_schedule(input).unwrap(), 295);
+    let notes = Notes::load("test_day13").unwrap();
+    assert_eq!(find_schedule(&notes).unwrap(), 295);
 
     assert_eq!(mod_inverse(10, 17).unwrap(), 12);
-    let input = utils::get_input("test_day13_2");
-    assert_eq!(chinese_remainder_theorem(input).unwrap(), 3417);
+    let notes = Notes::load("test_day13_2").unwrap();
+    assert_eq!(chinese_remainder_theorem(&notes).unwrap(), 3417);
 }
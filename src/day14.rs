@@ -1,15 +1,6 @@
-#![allow(dead_code)]
-
-use crate::{regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{solution::Solution, utils};
 use std::collections::HashMap;
-use utils::{AOCResult};
-
-lazy_static! {
-    static ref MEM_REGEX: Regex = regex!(r"^mem\[([0-9]+)\] = ([0-9]+)$");
-    static ref MASK_REGEX: Regex = regex!(r"^mask = ([X01]+)$");
-}
+use utils::{AOCResult, Input};
 
 type Mask = (u64, u64, u64);
 
@@ -24,21 +15,18 @@ pub struct Program {
 }
 
 impl Program {
-    pub fn load(filename: &str) -> AOCResult<Self> {
-        let input = utils::get_input(filename);
-        let mut instructions = Vec::new();
-        for line in input {
-            let instruction = if let Some(capture) = MASK_REGEX.captures_iter(&line).next() {
-                Instruction::Mask(Program::mask_instruction(&capture[1]))
+    pub fn load(input: impl Into<Input>) -> AOCResult<Self> {
+        let instructions = utils::get_input(input)?.parse_each(|p| {
+            Ok(if p.eat("mask = ") {
+                let mask = p.take_while(|c| matches!(c, 'X' | '0' | '1'));
+                Instruction::Mask(Program::mask_instruction(mask))
             } else {
-                let mem_capture = MEM_REGEX.captures_iter(&line).next()?;
-                Instruction::Memory{
-                    address: mem_capture[1].parse::<u64>()?,
-                    value: mem_capture[2].parse::<u64>()?,
-                }
-            };
-            instructions.push(instruction);
-        }
+                p.literal("mem[")?;
+                let address = p.int()?;
+                p.literal("] = ")?;
+                Instruction::Memory { address, value: p.int()? }
+            })
+        })?;
 
         Ok(Program {
             instructions,
@@ -130,10 +118,27 @@ impl Program {
     }
 }
 
-pub fn day14() {
-    let program = Program::load("day14").unwrap();
-    println!("docking_data part 1: {}", program.part_1());
-    println!("docking_data part 2: {}", program.part_2());
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Model = Program;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    const DAY: u8 = 14;
+    const NAME: &'static str = "docking_data";
+
+    fn parse(input: &Input) -> AOCResult<Program> {
+        Program::load(input)
+    }
+
+    fn part1(program: &Program) -> AOCResult<u64> {
+        Ok(program.part_1())
+    }
+
+    fn part2(program: &Program) -> AOCResult<u64> {
+        Ok(program.part_2())
+    }
 }
 
 #[test]
@@ -1,18 +1,28 @@
-#![allow(dead_code)]
-
-use crate::{regex};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{solution::Solution, utils};
+use utils::{AOCResult, Input};
 use std::collections::HashMap;
 
-lazy_static! {
-    static ref MEM_REGEX: Regex = regex!(r"^mem\[([0-9]+)\] = ([0-9]+)$");
-    static ref MASK_REGEX: Regex = regex!(r"^mask = ([X01]+)$");
-}
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Model = Vec<u64>;
+    type Part1 = u64;
+    type Part2 = u64;
 
-pub fn day15() {
-    println!("rambunctious_recitation part 1: {:?}", recitation(vec![12,20,0,6,1,17,7], 2020));
-    println!("rambunctious_recitation part 1: {:?}", recitation(vec![12,20,0,6,1,17,7], 30000000));
+    const DAY: u8 = 15;
+    const NAME: &'static str = "rambunctious_recitation";
+
+    fn parse(input: &Input) -> AOCResult<Vec<u64>> {
+        utils::get_input(input)?.parse_next(|p| p.list(",", |p| p.int()))
+    }
+
+    fn part1(starting: &Vec<u64>) -> AOCResult<u64> {
+        Ok(recitation(starting.clone(), 2020))
+    }
+
+    fn part2(starting: &Vec<u64>) -> AOCResult<u64> {
+        Ok(recitation(starting.clone(), 30000000))
+    }
 }
 
 pub fn recitation(input: Vec<u64>, max_turn: u64) -> u64 {
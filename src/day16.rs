@@ -1,71 +1,70 @@
-#![allow(dead_code)]
-
-use crate::{regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{solution::Solution, utils};
 use std::collections::{HashMap, HashSet};
 use std::ops::RangeInclusive;
-use utils::{AOCError, AOCResult};
-
-lazy_static! {
-    static ref SCHEMA_REGEX: Regex = regex!(r"^([a-z ]+): ([0-9]+)-([0-9]+) or ([0-9]+)-([0-9]+)$");
-}
+use utils::{AOCResult, Input, Lines, OptionExt, Parser};
 
 type TicketRange = (RangeInclusive<u32>, RangeInclusive<u32>);
 type TicketSchema = HashMap<String, TicketRange>;
 type Ticket = Vec<u32>;
 
-fn load_ticket_values(line: &str) -> AOCResult<Ticket> {
-    line.split(",")
-        .map(|value| value.parse::<u32>())
-        .collect::<Result<Ticket, _>>()
-        .map_err(|e| AOCError::from(e))
+pub struct Notes {
+    schema: TicketSchema,
+    mine: Ticket,
+    nearby: Vec<Ticket>,
 }
 
-pub fn load_schema(input: &mut impl Iterator<Item = String>) -> AOCResult<TicketSchema> {
-    let mut schema: TicketSchema = HashMap::new();
-    for line in input {
-        let mut captures = SCHEMA_REGEX.captures_iter(&line);
-        if let Some(capture) = captures.next() {
-            schema.insert(
-                capture[1].to_owned(),
-                (
-                    (capture[2].parse::<u32>()?..=capture[3].parse::<u32>()?),
-                    (capture[4].parse::<u32>()?..=capture[5].parse::<u32>()?),
-                ),
-            );
-        } else {
-            break;
-        }
-    }
+fn load_range(p: &mut Parser) -> AOCResult<RangeInclusive<u32>> {
+    let start = p.int()?;
+    p.literal("-")?;
+    Ok(start..=p.int()?)
+}
 
-    Ok(schema)
+// "departure location: 25-80 or 90-961"
+pub fn load_schema(input: &mut Lines) -> AOCResult<TicketSchema> {
+    Ok(input
+        .parse_each(|p| {
+            let name = p.until(": ")?.to_owned();
+            let r1 = load_range(p)?;
+            p.literal(" or ")?;
+            Ok((name, (r1, load_range(p)?)))
+        })?
+        .into_iter()
+        .collect())
 }
 
-pub fn part1(filename: &str) -> u32 {
-    let mut input = utils::get_input(filename);
-    let valid_ranges: Vec<TicketRange> = load_schema(&mut input).unwrap().into_values().collect();
+fn load_tickets(input: &mut Lines, header: &str) -> AOCResult<Vec<Ticket>> {
+    input.parse_next(|p| p.literal(header))?;
+    input.parse_each(|p| p.list(",", |p| p.int()))
+}
+
+impl Notes {
+    pub fn load(input: impl Into<Input>) -> AOCResult<Notes> {
+        let mut input = utils::get_input(input)?;
+        let mut section = || input.section().ok_or_none();
+        let schema = load_schema(&mut section()?)?;
+        let mine = load_tickets(&mut section()?, "your ticket:")?.pop().ok_or_none()?;
+        let nearby = load_tickets(&mut section()?, "nearby tickets:")?;
+        Ok(Notes { schema, mine, nearby })
+    }
+}
+
+pub fn part1(notes: &Notes) -> u32 {
+    let valid_ranges: Vec<&TicketRange> = notes.schema.values().collect();
 
-    input.skip(4).fold(0, |acc, line| {
-        load_ticket_values(&line)
-            .unwrap()
+    notes.nearby.iter().flatten().fold(0, |acc, value| {
+        if !valid_ranges
             .iter()
-            .fold(acc, |acc, value| {
-                if !valid_ranges
-                    .iter()
-                    .any(|(r1, r2)| r1.contains(value) || r2.contains(value))
-                {
-                    acc + value
-                } else {
-                    acc
-                }
-            })
+            .any(|(r1, r2)| r1.contains(value) || r2.contains(value))
+        {
+            acc + value
+        } else {
+            acc
+        }
     })
 }
 
-pub fn part2(filename: &str) -> u64 {
-    let mut input = utils::get_input(filename);
-    let ticket_schema = load_schema(&mut input).unwrap();
+pub fn part2(notes: &Notes) -> u64 {
+    let ticket_schema = &notes.schema;
     let base_options = ticket_schema.keys().collect::<HashSet<_>>();
     let valid_ranges = ticket_schema.values().collect::<Vec<_>>();
 
@@ -77,12 +76,9 @@ pub fn part2(filename: &str) -> u64 {
         ticket_options.push(base_options.clone());
     }
 
-    input.next();
-    let my_ticket = load_ticket_values(&input.next().unwrap()).unwrap();
-
     let mut solved_queue = Vec::new();
-    'outer: for line in input.skip(2) {
-        for (i, value) in load_ticket_values(&line).unwrap().iter().enumerate() {
+    'outer: for ticket in notes.nearby.iter() {
+        for (i, value) in ticket.iter().enumerate() {
             if solved_options[i].is_some() {
                 continue;
             }
@@ -94,10 +90,10 @@ pub fn part2(filename: &str) -> u64 {
             }
 
             let options = ticket_options.get_mut(i).unwrap();
-            options.drain_filter(|field| {
+            options.retain(|field| {
                 let (r1, r2) = &ticket_schema[*field];
-                !r1.contains(value) && !r2.contains(value)
-            }).for_each(drop);
+                r1.contains(value) || r2.contains(value)
+            });
 
             if options.len() == 1 {
                 let field = options.iter().next().unwrap().to_owned();
@@ -121,9 +117,7 @@ pub fn part2(filename: &str) -> u64 {
         }
     }
 
-    println!("Solved for {:?}", solved_options);
-
-    my_ticket.iter().enumerate().fold(1, |a, (i, value)|
+    notes.mine.iter().enumerate().fold(1, |a, (i, value)|
         a * match solved_options[i] {
             Some(x) if x.starts_with("departure") => *value,
             _ => 1,
@@ -131,12 +125,30 @@ pub fn part2(filename: &str) -> u64 {
     )
 }
 
-pub fn day16() {
-    println!("ticket translation part 1: {}", part1("day16"));
-    println!("ticket translation part 2: {}", part2("day16"));
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Model = Notes;
+    type Part1 = u32;
+    type Part2 = u64;
+
+    const DAY: u8 = 16;
+    const NAME: &'static str = "ticket_translation";
+
+    fn parse(input: &Input) -> AOCResult<Notes> {
+        Notes::load(input)
+    }
+
+    fn part1(notes: &Notes) -> AOCResult<u32> {
+        Ok(part1(notes))
+    }
+
+    fn part2(notes: &Notes) -> AOCResult<u64> {
+        Ok(part2(notes))
+    }
 }
 
 #[test]
 fn test_day16() {
-    assert_eq!(part1("test_day16"), 71);
+    assert_eq!(part1(&Notes::load("test_day16").unwrap()), 71);
 }
@@ -1,91 +1,109 @@
-#![allow(dead_code)]
-
-use crate::utils;
-use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use crate::{animate::{Animation, Take}, solution::Solution, utils};
+use utils::{pixel, AOCResult, Automaton, Grid2D, Input, OptionExt, Sparse};
+use std::fmt;
 
+#[derive(Clone)]
 pub struct ConwayGrid<const N: usize> {
-    active_cells: HashSet<[i32; N]>,
+    active_cells: Sparse<[i32; N]>,
 }
 
 impl<const N: usize> ConwayGrid<{ N }> {
-    pub fn load(filename: &str) -> ConwayGrid<{ N }> {
-        let input = utils::get_input(filename);
-
-        ConwayGrid {
-            active_cells: input
-                .enumerate()
-                .flat_map(|(i, line)| {
-                    line.chars()
-                        .enumerate()
-                        .filter_map(|(j, c)| match c {
-                            '#' => {
-                                let mut coordinates = [0; N];
-                                coordinates[N - 1] = j as i32;
-                                coordinates[N - 2] = i as i32;
-                                Some(coordinates)
-                            }
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<HashSet<_>>(),
-        }
+    pub fn load(input: impl Into<Input>) -> AOCResult<ConwayGrid<{ N }>> {
+        let grid = Grid2D::parse(&mut utils::get_input(input)?, pixel)?;
+        let active = grid
+            .iter()
+            .filter(|(_, active)| **active)
+            .map(|((x, y), _)| {
+                let mut coordinates = [0; N];
+                coordinates[N - 1] = x as i32;
+                coordinates[N - 2] = y as i32;
+                coordinates
+            })
+            .collect();
+
+        Ok(ConwayGrid {
+            active_cells: Sparse::lattice(active, |active, count| count == 3 || (active && count == 2)),
+        })
     }
 
     fn into_iter(mut self) -> impl Iterator<Item = usize> {
         std::iter::from_fn(move || Some(self.step()))
     }
 
-    fn step(&mut self) -> usize {
-        let mut cells = HashMap::new();
-        for cell in self
-            .active_cells
-            .iter()
-            .flat_map(|cell| Self::neighbors(cell))
-        {
-            *cells.entry(cell).or_insert(0) += 1;
+    pub fn step(&mut self) -> usize {
+        self.active_cells.step();
+        self.active_cells.len()
+    }
+}
+
+impl<const N: usize> Animation for ConwayGrid<{ N }> {
+    fn step(&mut self) -> bool {
+        ConwayGrid::step(self);
+        true
+    }
+}
+
+// Draws each x-y slice that has active cubes, like the puzzle does, all
+// cropped to the same bounds.
+impl<const N: usize> fmt::Display for ConwayGrid<{ N }> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self.active_cells.live();
+        let bounds = |axis: usize| {
+            let values = cells.iter().map(|c| c[axis]);
+            (values.clone().min().unwrap_or(0), values.max().unwrap_or(0))
+        };
+        let ((x0, x1), (y0, y1)) = (bounds(N - 1), bounds(N - 2));
+
+        let mut slices = cells.iter().map(|c| c[..N - 2].to_vec()).collect::<Vec<_>>();
+        slices.sort_unstable();
+        slices.dedup();
+        for (i, slice) in slices.iter().enumerate() {
+            let label = slice.iter().rev().zip(["z", "w"].iter()).map(|(v, axis)| format!("{}={}", axis, v));
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}", label.collect::<Vec<_>>().join(", "))?;
+
+            let mut grid = Grid2D::new((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize, false);
+            for cell in cells.iter().filter(|c| c[..N - 2] == slice[..]) {
+                grid[((cell[N - 1] - x0) as usize, (cell[N - 2] - y0) as usize)] = true;
+            }
+            write!(f, "{}", grid)?;
         }
+        Ok(())
+    }
+}
 
-        self.active_cells = cells
-            .into_iter()
-            .filter_map(|(cell, count)| {
-                if count == 3 || (count == 2 && self.active_cells.contains(&cell)) {
-                    Some(cell)
-                } else {
-                    None
-                }
-            })
-            .collect::<HashSet<_>>();
+pub struct Day17;
 
-        self.active_cells.len()
+impl Solution for Day17 {
+    type Model = (ConwayGrid<3>, ConwayGrid<4>);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 17;
+    const NAME: &'static str = "conway_cubes";
+
+    fn parse(input: &Input) -> AOCResult<Self::Model> {
+        Ok((ConwayGrid::load(input)?, ConwayGrid::load(input)?))
     }
 
-    fn neighbors(coordinates: &[i32; N]) -> impl Iterator<Item = [i32; N]> + '_ {
-        let base_coordinates = coordinates.clone();
-        std::iter::repeat(-1..=1)
-            .take(N)
-            .multi_cartesian_product()
-            .filter_map(move |delta| {
-                let mut neighbor = base_coordinates.clone();
-                for i in 0..N {
-                    neighbor[i] += delta[i];
-                }
-                if neighbor == base_coordinates {
-                    None
-                } else {
-                    Some(neighbor)
-                }
-            })
+    fn part1((grid, _): &Self::Model) -> AOCResult<usize> {
+        grid.clone().into_iter().nth(5).ok_or_none()
     }
-}
 
-pub fn day17() {
-    let mut conwaygrid = ConwayGrid::<3>::load("day17").into_iter();
-    println!("conway_cubes part 1: {:?}", conwaygrid.nth(5).unwrap());
+    fn part2((_, grid): &Self::Model) -> AOCResult<usize> {
+        grid.clone().into_iter().nth(5).ok_or_none()
+    }
 
-    let mut conwaygrid = ConwayGrid::<4>::load("day17").into_iter();
-    println!("conway_cubes part 2: {:?}", conwaygrid.nth(5).unwrap());
+    // The six boot cycles, in 3D or 4D.
+    fn animate((grid3, grid4): &Self::Model, part: u8) -> Option<Box<dyn Animation>> {
+        if part == 1 {
+            Some(Box::new(Take::new(grid3.clone(), 6)))
+        } else {
+            Some(Box::new(Take::new(grid4.clone(), 6)))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -94,10 +112,19 @@ mod tests {
 
     #[test]
     pub fn test_day17() {
-        let mut conwaygrid = ConwayGrid::<3>::load("test_day17").into_iter();
+        let mut conwaygrid = ConwayGrid::<3>::load("test_day17").unwrap().into_iter();
         assert_eq!(conwaygrid.nth(1).unwrap(), 21);
 
-        let mut conwaygrid = ConwayGrid::<4>::load("test_day17").into_iter();
+        let mut conwaygrid = ConwayGrid::<4>::load("test_day17").unwrap().into_iter();
         assert_eq!(conwaygrid.nth(5).unwrap(), 848);
     }
+
+    #[test]
+    pub fn test_day17_display() {
+        let mut conwaygrid = ConwayGrid::<3>::load("test_day17").unwrap();
+        assert_eq!(conwaygrid.to_string(), "z=0\n.#.\n..#\n###\n");
+        conwaygrid.step();
+        assert!(conwaygrid.to_string().starts_with("z=-1\n#..\n..#\n.#.\n\nz=0\n"));
+        assert_eq!(conwaygrid.step(), 21);
+    }
 }
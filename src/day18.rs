@@ -1,23 +1,21 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, utils};
+use crate::{aocbail, solution::Solution, utils};
 use std::iter::Peekable;
-use utils::{AOCError, AOCResult};
+use utils::{AOCResult, Input, OptionExt};
 
 pub fn re_weird_parse(
     tokens: &mut Peekable<impl Iterator<Item = char>>,
     precedence: bool,
     greedy: bool,
 ) -> AOCResult<u64> {
-    let mut left_expr = match tokens.next()? {
+    let mut left_expr = match tokens.next().ok_or_none()? {
         '(' => {
             let expr = re_weird_parse(tokens, precedence, false)?;
-            if tokens.next()? != ')' {
+            if tokens.next().ok_or_none()? != ')' {
                 aocbail!("Expected closing parens");
             }
             expr
         }
-        x => x.to_digit(10)? as u64,
+        x => x.to_digit(10).ok_or_none()? as u64,
     };
 
     while let Some(token) = tokens.peek() {
@@ -25,7 +23,7 @@ pub fn re_weird_parse(
             break;
         }
 
-        let next_add = tokens.next()? == '+';
+        let next_add = tokens.next().ok_or_none()? == '+';
         let right_expr = re_weird_parse(tokens, precedence, !precedence || next_add)?;
 
         if next_add {
@@ -46,16 +44,27 @@ fn weird_parse(input: &str, precedence: bool) -> AOCResult<u64> {
     )
 }
 
-pub fn day18() {
-    let (s1, s2) = utils::get_input("day18").fold((0, 0), |(a1, a2), line| {
-        (
-            a1 + weird_parse(&line, false).unwrap(),
-            a2 + weird_parse(&line, true).unwrap(),
-        )
-    });
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Model = Vec<String>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    const DAY: u8 = 18;
+    const NAME: &'static str = "operation_order";
+
+    fn parse(input: &Input) -> AOCResult<Vec<String>> {
+        Ok(utils::get_input(input)?.collect())
+    }
+
+    fn part1(homework: &Vec<String>) -> AOCResult<u64> {
+        homework.iter().map(|line| weird_parse(line, false)).sum()
+    }
 
-    println!("operation_order part 1: {:?}", s1);
-    println!("operation_order part 2: {:?}", s2);
+    fn part2(homework: &Vec<String>) -> AOCResult<u64> {
+        homework.iter().map(|line| weird_parse(line, true)).sum()
+    }
 }
 
 #[cfg(test)]
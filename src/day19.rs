@@ -1,14 +1,7 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{aocbail, solution::Solution, utils};
 use std::collections::HashMap;
-use utils::{AOCError, AOCResult};
-
-lazy_static! {
-    static ref RULE_REGEX: Regex = regex!(r"^([0-9]+): ");
-}
+use std::fmt;
+use utils::{AOCResult, Input, Lines, OptionExt, Parser, Rng};
 
 #[derive(Debug)]
 pub enum Rule {
@@ -25,44 +18,70 @@ pub struct Grammar {
 }
 
 impl Rule {
-    pub fn load(input: &str) -> AOCResult<Rule> {
-        Ok(match input.trim() {
-            "\"a\"" => Rule::A,
-            "\"b\"" => Rule::B,
-            s if s.contains("|") => {
-                let subrules = s.split('|').collect::<Vec<_>>();
-                Rule::Or(vec![Rule::load(subrules[0])?, Rule::load(subrules[1])?])
-            }
-            s if s.contains(" ") => Rule::And(
-                s.split(' ')
-                    .map(|subrule| Rule::load(subrule))
-                    .collect::<AOCResult<Vec<_>>>()?,
-            ),
-            s => Rule::Id(s.parse::<usize>()?),
+    // "\"a\"", or alternatives of rule id sequences such as "4 1 | 1 4".
+    pub fn parse(p: &mut Parser) -> AOCResult<Rule> {
+        if p.eat("\"a\"") {
+            return Ok(Rule::A);
+        }
+        if p.eat("\"b\"") {
+            return Ok(Rule::B);
+        }
+        let mut alternatives = p.list(" | ", Rule::sequence)?;
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Rule::Or(alternatives),
+        })
+    }
+
+    fn sequence(p: &mut Parser) -> AOCResult<Rule> {
+        let mut ids = vec![Rule::Id(p.int()?)];
+        while p.peek(" ") && !p.peek(" |") {
+            p.literal(" ")?;
+            ids.push(Rule::Id(p.int()?));
+        }
+        Ok(match ids.len() {
+            1 => ids.remove(0),
+            _ => Rule::And(ids),
         })
     }
 }
 
-impl Grammar {
-    pub fn load(input: &mut impl Iterator<Item = String>) -> AOCResult<Grammar> {
-        let mut rules = HashMap::new();
-        while let Some(line) = input.next() {
-            if line.len() == 0 {
-                break;
+// The rule as it is written in the input.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, rules: &[Rule], separator| {
+            for (i, rule) in rules.iter().enumerate() {
+                write!(f, "{}{}", if i == 0 { "" } else { separator }, rule)?;
             }
-            let mut rule_parts = line.split(":");
-            rules.insert(
-                rule_parts.next()?.parse::<usize>()?,
-                Rule::load(rule_parts.next()?)?,
-            );
+            Ok(())
+        };
+        match self {
+            Rule::And(rules) => join(f, rules, " "),
+            Rule::Or(rules) => join(f, rules, " | "),
+            Rule::Id(id) => write!(f, "{}", id),
+            Rule::A => write!(f, "\"a\""),
+            Rule::B => write!(f, "\"b\""),
         }
+    }
+}
+
+impl Grammar {
+    // Reads rules up to the first blank line.
+    pub fn load(input: &mut Lines) -> AOCResult<Grammar> {
+        let rules = input.section().ok_or_none()?.parse_each(|p| {
+            let id = p.int::<usize>()?;
+            p.literal(": ")?;
+            Ok((id, Rule::parse(p)?))
+        })?;
 
-        Ok(Grammar { rules })
+        Ok(Grammar {
+            rules: rules.into_iter().collect(),
+        })
     }
 
     pub fn eats(&self, input: &str) -> bool {
         match self.eat(&self.rules[&0], input) {
-            Ok(leftover) => leftover.len() == 0,
+            Ok(leftover) => leftover.is_empty(),
             Err(_) => false,
         }
     }
@@ -78,11 +97,22 @@ impl Grammar {
         })
     }
 
+    // A random message that `rule` matches.
+    pub fn sample(&self, rule: &Rule, rng: &mut Rng, out: &mut String) {
+        match rule {
+            Rule::And(rules) => rules.iter().for_each(|rule| self.sample(rule, rng, out)),
+            Rule::Or(rules) => self.sample(rng.pick(rules), rng, out),
+            Rule::Id(id) => self.sample(&self.rules[id], rng, out),
+            Rule::A => out.push('a'),
+            Rule::B => out.push('b'),
+        }
+    }
+
     fn repeat_eat(&self, mut input: &str, id: usize) -> i32 {
         let mut count = 0;
         while let Ok(submatch) = self.eat(&self.rules[&id], input) {
             count += 1;
-            if submatch.len() == 0 {
+            if submatch.is_empty() {
                 return count;
             }
             input = submatch;
@@ -92,8 +122,8 @@ impl Grammar {
 
     fn eat<'a>(&self, rule: &Rule, mut input: &'a str) -> AOCResult<&'a str> {
         Ok(match rule {
-            Rule::A if input.starts_with('a') => input.get(1..)?,
-            Rule::B if input.starts_with('b') => input.get(1..)?,
+            Rule::A if input.starts_with('a') => input.get(1..).ok_or_none()?,
+            Rule::B if input.starts_with('b') => input.get(1..).ok_or_none()?,
             Rule::Id(id) => self.eat(&self.rules[id], input)?,
             Rule::And(rules) => {
                 for rule in rules.iter() {
@@ -114,17 +144,139 @@ impl Grammar {
     }
 }
 
-pub fn day19() {
-    let mut input = utils::get_input("day19");
-    let grammar = Grammar::load(&mut input).unwrap();
-    let (s1, s2) = input.fold((0, 0), |(a1, a2), line| {
-        (
-            a1 + if grammar.eats(&line) { 1 } else { 0 },
-            a2 + if grammar.eats_loop(&line) { 1 } else { 0 },
-        )
-    });
-    println!("monster_messages part 1: {}", s1);
-    println!("monster_messages part 2: {}", s2);
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Model = (Grammar, Vec<String>);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 19;
+    const NAME: &'static str = "monster_messages";
+
+    fn parse(input: &Input) -> AOCResult<Self::Model> {
+        let mut input = utils::get_input(input)?;
+        let grammar = Grammar::load(&mut input)?;
+        Ok((grammar, input.collect()))
+    }
+
+    fn part1((grammar, messages): &Self::Model) -> AOCResult<usize> {
+        Ok(messages.iter().filter(|m| grammar.eats(m)).count())
+    }
+
+    fn part2((grammar, messages): &Self::Model) -> AOCResult<usize> {
+        Ok(messages.iter().filter(|m| grammar.eats_loop(m)).count())
+    }
+
+    // `size` messages, 450 by default like the real input.
+    fn generate(rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Some(random_grammar(rng, size.unwrap_or(450)))
+    }
+}
+
+// Rules whose ids the puzzle fixes: 0: 8 11, 8: 42 and 11: 42 31.
+const FIXED: [usize; 5] = [0, 8, 11, 31, 42];
+// The generator's rules for "a", "b" and either letter.
+const GEN_A: usize = 1;
+const GEN_B: usize = 2;
+const GEN_EITHER: usize = 3;
+
+// Adds a rule matching some strings of exactly `len` letters, or reuses an
+// earlier one. Alternatives always start with different letters, so the
+// first one that matches is the only one that could.
+fn random_rule(rng: &mut Rng, rules: &mut HashMap<usize, Rule>, by_len: &mut Vec<Vec<usize>>, len: usize) -> usize {
+    if len == 1 {
+        return *rng.pick(&[GEN_A, GEN_B, GEN_EITHER]);
+    }
+    if let Some(existing) = by_len.get(len).filter(|ids| !ids.is_empty() && rng.chance(0.2)) {
+        return *rng.pick(existing);
+    }
+    let rule = if rng.chance(0.5) {
+        let (a, b) = (random_rule(rng, rules, by_len, len - 1), random_rule(rng, rules, by_len, len - 1));
+        let branch = |letter, rest| Rule::And(vec![Rule::Id(letter), Rule::Id(rest)]);
+        Rule::Or(vec![branch(GEN_A, a), branch(GEN_B, b)])
+    } else {
+        let split = 1 + rng.below(len - 1);
+        Rule::And(vec![Rule::Id(random_rule(rng, rules, by_len, split)), Rule::Id(random_rule(rng, rules, by_len, len - split))])
+    };
+    let id = (rules.len()..).find(|id| !rules.contains_key(id) && !FIXED.contains(id)).unwrap();
+    rules.insert(id, rule);
+    by_len.resize(by_len.len().max(len + 1), Vec::new());
+    by_len[len].push(id);
+    id
+}
+
+fn renumber(rule: &mut Rule, ids: &HashMap<usize, usize>) {
+    match rule {
+        Rule::And(rules) | Rule::Or(rules) => rules.iter_mut().for_each(|rule| renumber(rule, ids)),
+        Rule::Id(id) => *id = ids[id],
+        Rule::A | Rule::B => {}
+    }
+}
+
+// A grammar shaped like the real one, where 42 and 31 match disjoint sets
+// of 8 letter chunks, followed by `messages` messages: some of 42 42 31,
+// some only the looping rules of part 2 accept, and some neither does.
+pub fn random_grammar(rng: &mut Rng, messages: usize) -> String {
+    const CHUNK: usize = 8;
+    let mut rules = HashMap::new();
+    let mut by_len = Vec::new();
+    rules.insert(GEN_A, Rule::A);
+    rules.insert(GEN_B, Rule::B);
+    rules.insert(GEN_EITHER, Rule::Or(vec![Rule::Id(GEN_A), Rule::Id(GEN_B)]));
+
+    let (a, b) = (random_rule(rng, &mut rules, &mut by_len, CHUNK - 1), random_rule(rng, &mut rules, &mut by_len, CHUNK - 1));
+    rules.insert(42, Rule::And(vec![Rule::Id(GEN_A), Rule::Id(a)]));
+    rules.insert(31, Rule::And(vec![Rule::Id(GEN_B), Rule::Id(b)]));
+    rules.insert(11, Rule::And(vec![Rule::Id(42), Rule::Id(31)]));
+    rules.insert(8, Rule::Id(42));
+    rules.insert(0, Rule::And(vec![Rule::Id(8), Rule::Id(11)]));
+
+    let mut free = rules.keys().copied().filter(|id| !FIXED.contains(id)).collect::<Vec<_>>();
+    free.sort_unstable();
+    let mut shuffled = free.clone();
+    rng.shuffle(&mut shuffled);
+    let ids = FIXED.iter().map(|id| (*id, *id)).chain(free.into_iter().zip(shuffled)).collect::<HashMap<_, _>>();
+    let grammar = Grammar {
+        rules: rules
+            .into_iter()
+            .map(|(id, mut rule)| {
+                renumber(&mut rule, &ids);
+                (ids[&id], rule)
+            })
+            .collect(),
+    };
+
+    // Sorted first, since the map's own order changes from run to run.
+    let mut order = grammar.rules.keys().copied().collect::<Vec<_>>();
+    order.sort_unstable();
+    rng.shuffle(&mut order);
+    let mut lines = order.iter().map(|id| format!("{}: {}", id, grammar.rules[id])).collect::<Vec<_>>();
+    lines.push(String::new());
+    for _ in 0..messages {
+        let (n42, n31) = match rng.below(4) {
+            0 => (2, 1),
+            1 => {
+                let n31 = 1 + rng.below(3);
+                (n31 + 1 + rng.below(3), n31)
+            }
+            2 => {
+                let n31 = 1 + rng.below(3);
+                (1 + rng.below(n31), n31)
+            }
+            _ => (0, 0),
+        };
+        let mut message = String::new();
+        if n42 == 0 {
+            let len = CHUNK * (2 + rng.below(4)) + rng.below(2);
+            message = (0..len).map(|_| *rng.pick(&['a', 'b'])).collect();
+        }
+        for id in std::iter::repeat_n(42, n42).chain(std::iter::repeat_n(31, n31)) {
+            grammar.sample(&Rule::Id(id), rng, &mut message);
+        }
+        lines.push(message);
+    }
+    lines.join("\n") + "\n"
 }
 
 #[cfg(test)]
@@ -133,7 +285,7 @@ mod tests {
 
     #[test]
     pub fn test_day19() {
-        let mut test_input = utils::get_input("test_day19");
+        let mut test_input = utils::get_input("test_day19").unwrap();
         let grammar = Grammar::load(&mut test_input).unwrap();
 
         assert!(grammar.eats("ababbb"));
@@ -145,7 +297,7 @@ mod tests {
 
     #[test]
     pub fn test_day19_loop() {
-        let mut input = utils::get_input("test_day19_2");
+        let mut input = utils::get_input("test_day19_2").unwrap();
         let grammar = Grammar::load(&mut input).unwrap();
 
         assert!(grammar.eats_loop("babbbbaabbbbbabbbbbbaabaaabaaa"));
@@ -159,4 +311,40 @@ mod tests {
             12
         );
     }
+
+    // Every rule id `rule` refers to.
+    fn ids(rule: &Rule, out: &mut Vec<usize>) {
+        match rule {
+            Rule::And(rules) | Rule::Or(rules) => rules.iter().for_each(|rule| ids(rule, out)),
+            Rule::Id(id) => out.push(*id),
+            Rule::A | Rule::B => {}
+        }
+    }
+
+    #[test]
+    pub fn test_day19_generate() {
+        for seed in 0..5 {
+            let text = random_grammar(&mut Rng::new(seed), 200);
+            let mut input = utils::get_input(Input::Text(text.clone())).unwrap();
+            let grammar = Grammar::load(&mut input).unwrap();
+            let messages = input.collect::<Vec<_>>();
+            assert_eq!(messages.len(), 200);
+            assert!(messages.iter().all(|m| !m.is_empty() && m.chars().all(|c| c == 'a' || c == 'b')));
+
+            // The rules part 2 replaces are the puzzle's, and every rule
+            // referred to exists.
+            for rule in ["0: 8 11", "8: 42", "11: 42 31"].iter() {
+                assert!(text.lines().any(|line| line == *rule), "no rule {}", rule);
+            }
+            let mut used = Vec::new();
+            grammar.rules.values().for_each(|rule| ids(rule, &mut used));
+            assert!(used.iter().all(|id| grammar.rules.contains_key(id)));
+
+            // Looping only ever accepts more, and there are messages both
+            // parts accept, only part 2 accepts and neither does.
+            let kinds = messages.iter().map(|m| (grammar.eats(m), grammar.eats_loop(m))).collect::<std::collections::HashSet<_>>();
+            assert!(!kinds.contains(&(true, false)));
+            assert_eq!(kinds.len(), 3);
+        }
+    }
 }
@@ -1,52 +1,73 @@
-#![allow(dead_code)]
+use crate::{solution::Solution, utils};
+use utils::{parse_line, AOCResult, Input, Parser};
 
-use crate::{aocbail, utils, utils::AOCError};
-use regex;
+// "1-3 a: abcde": two numbers, a letter and the password they apply to.
+pub struct Policy {
+    first: usize,
+    second: usize,
+    letter: char,
+    password: String,
+}
 
-pub fn day2() {
-    let password_input = utils::get_input("day2");
-    let count = password_input.fold((0, 0), |a: (usize, usize), line: String| {
-        (
-            if p1(&line).unwrap() { a.0 + 1 } else { a.0 },
-            if p2(&line).unwrap() { a.1 + 1 } else { a.1 },
-        )
-    });
+impl Policy {
+    pub fn parse(p: &mut Parser) -> AOCResult<Policy> {
+        let first = p.int()?;
+        p.literal("-")?;
+        let second = p.int()?;
+        p.literal(" ")?;
+        let letter = p.char()?;
+        p.literal(": ")?;
+        let password = p.word()?.to_owned();
+        Ok(Policy {
+            first,
+            second,
+            letter,
+            password,
+        })
+    }
 
-    // 434
-    println!("password philosophy part 1: {:?}", count.0);
+    // The letter occurs between `first` and `second` times.
+    fn sled_rental(&self) -> bool {
+        let count = self.password.chars().filter(|c| *c == self.letter).count();
+        count >= self.first && count <= self.second
+    }
 
-    // 509
-    println!("password philosophy part 2: {:?}", count.1);
+    // The letter is at exactly one of the 1-based positions.
+    fn toboggan(&self) -> bool {
+        let at = |i: usize| i > 0 && self.password.chars().nth(i - 1) == Some(self.letter);
+        at(self.first) ^ at(self.second)
+    }
 }
 
-pub fn p1(input: &str) -> Result<bool, AOCError> {
-    let re = regex::Regex::new(r"^([\d]+)-([\d]+) ([a-z]): ([a-z]+)$")?;
-    for cap in re.captures_iter(input) {
-        let min: usize = cap[1].parse()?;
-        let max: usize = cap[2].parse()?;
-        let target_letter = cap[3].chars().next()?;
-        let password = &cap[4];
-
-        let count = password.chars().fold(
-            0,
-            |a: usize, c: char| if c == target_letter { a + 1 } else { a },
-        );
-        return Ok(count >= min && count <= max);
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Model = Vec<Policy>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 2;
+    const NAME: &'static str = "password_philosophy";
+
+    fn parse(input: &Input) -> AOCResult<Vec<Policy>> {
+        utils::get_input(input)?.parse_each(Policy::parse)
     }
-    aocbail!("Didn't capture anything")
-}
 
-pub fn p2(input: &str) -> Result<bool, AOCError> {
-    let re = regex::Regex::new(r"^([\d]+)-([\d]+) ([a-z]): ([a-z]+)$")?;
-    for capture in re.captures_iter(input) {
-        let i: usize = capture[1].parse()?;
-        let j: usize = capture[2].parse()?;
-        let target = capture[3].chars().next()?;
-        let password: Vec<char> = capture[4].chars().collect();
+    fn part1(passwords: &Vec<Policy>) -> AOCResult<usize> {
+        Ok(passwords.iter().filter(|p| p.sled_rental()).count())
+    }
 
-        return Ok((password[i - 1] == target) ^ (password[j - 1] == target));
+    fn part2(passwords: &Vec<Policy>) -> AOCResult<usize> {
+        Ok(passwords.iter().filter(|p| p.toboggan()).count())
     }
-    aocbail!("Didn't capture anything")
+}
+
+pub fn p1(input: &str) -> AOCResult<bool> {
+    Ok(parse_line(input, Policy::parse)?.sled_rental())
+}
+
+pub fn p2(input: &str) -> AOCResult<bool> {
+    Ok(parse_line(input, Policy::parse)?.toboggan())
 }
 
 #[test]
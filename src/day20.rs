@@ -1,30 +1,36 @@
-#![allow(dead_code)]
-
-use crate::{regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{aocbail, solution::Solution, utils};
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
-use utils::AOCResult;
-
-lazy_static! {
-    static ref ID_REGEX: Regex = regex!(r"^Tile ([0-9]+):");
-}
-
-///
-/// we have a bunch of tiles
-/// each needs to be flipped or rotated so that edges line up in some way, shape, or form with other tiles
-/// first: parse out each tile
-/// for now we'll ignore the actual image content (we'll probably need it later tbh)
-///
-/// We have a tile map with some idea of associations; we can reconstruct our final image one row at a time.
-///   Figure out which tiles are Corners
-///   Per Corner:
-///
-///
+use utils::{parse_line, pixel, AOCResult, Glyph, Grid2D, Input, Lines, OptionExt, Rng, Span, SpanExt};
+
+//
+// we have a bunch of tiles
+// each needs to be flipped or rotated so that edges line up in some way, shape, or form with other tiles
+// first: parse out each tile
+// for now we'll ignore the actual image content (we'll probably need it later tbh)
+//
+// We have a tile map with some idea of associations; we can reconstruct our final image one row at a time.
+//   Figure out which tiles are Corners
+//   Per Corner:
+//
+//
 
 type TileEdge = [bool; 10];
 
+const SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+// (row, column) of every '#' in the sea monster.
+fn sea_monster() -> Vec<(usize, usize)> {
+    SEA_MONSTER
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| line.char_indices().filter(|(_, c)| *c == '#').map(move |(column, _)| (row, column)))
+        .collect()
+}
+
 #[derive(Default, Debug)]
 pub struct TileTransform {
     flipped_edge: Option<usize>,
@@ -40,11 +46,12 @@ pub struct TileEdgeMatch {
 #[derive(Clone)]
 pub struct Tile {
     id: usize,
-    image_content: [TileEdge; 10],
+    image_content: Grid2D<bool>,
     tile_edges: [TileEdge; 4],
     flipped_edges: [TileEdge; 4],
 }
 
+#[derive(Clone)]
 pub struct TilePuzzle {
     tiles: HashMap<usize, Tile>,
     associations: HashMap<usize, Vec<usize>>,
@@ -57,22 +64,30 @@ pub struct TileSolution {
 }
 
 pub struct TileImage {
-    pixels: Vec<Vec<bool>>,
+    pixels: Grid2D<bool>,
+    // Pixels that turned out to be part of a sea monster.
+    monsters: Grid2D<bool>,
 }
 
 impl Tile {
-    pub fn load(input: &mut impl Iterator<Item = String>) -> AOCResult<Tile> {
-        let id = ID_REGEX.captures_iter(&input.next()?).next()?[1].parse::<usize>()?;
-        let mut image_content = [[false; 10]; 10];
-        let mut i = 0;
-        for line in input {
-            if line.len() == 0 {
-                break;
-            }
-            for (j, c) in line.chars().enumerate() {
-                image_content[i][j] = c == '#';
-            }
-            i += 1;
+    pub fn load(input: &mut Lines) -> AOCResult<Tile> {
+        let header = input.next().ok_or_none()?;
+        let header_line = input.line();
+        let id = parse_line(&header, |p| {
+            p.literal("Tile ")?;
+            let id = p.int()?;
+            p.literal(":")?;
+            Ok(id)
+        })
+        .at_line(header_line)
+        .in_input(input.name())?;
+
+        let image_content = Grid2D::parse(input, pixel)?;
+        if (image_content.width(), image_content.height()) != (10, 10) {
+            aocbail!(
+                at Span::new(&header, &header).line(header_line).input(input.name());
+                "Tile {} is {}x{} pixels, expected 10x10", id, image_content.width(), image_content.height()
+            );
         }
 
         let mut tile = Tile {
@@ -88,10 +103,10 @@ impl Tile {
 
     fn update_edges(&mut self) {
         for i in 0..10 {
-            self.tile_edges[0][i] = self.image_content[0][i];
-            self.tile_edges[1][i] = self.image_content[9 - i][0];
-            self.tile_edges[2][i] = self.image_content[9][9 - i];
-            self.tile_edges[3][i] = self.image_content[i][9];
+            self.tile_edges[0][i] = self.image_content[(i, 0)];
+            self.tile_edges[1][i] = self.image_content[(0, 9 - i)];
+            self.tile_edges[2][i] = self.image_content[(9 - i, 9)];
+            self.tile_edges[3][i] = self.image_content[(9, i)];
         }
         for i in 0..10 {
             for j in 0..4 {
@@ -128,30 +143,17 @@ impl Tile {
     }
 
     pub fn flip_over_edge(&mut self, i: usize) {
-        let tmp_image = self.image_content;
-        for y in 0..10 {
-            for x in 0..10 {
-                if i % 2 == 0 {
-                    self.image_content[y][x] = tmp_image[y][9 - x];
-                } else {
-                    self.image_content[y][x] = tmp_image[9 - y][x];
-                }
-            }
-        }
+        self.image_content = if i.is_multiple_of(2) {
+            self.image_content.flip_horizontal()
+        } else {
+            self.image_content.flip_vertical()
+        };
         self.update_edges();
     }
 
     pub fn rotate_clockwise(&mut self, steps: usize) {
-        let tmp_image = self.image_content;
-        for i in 0..10 {
-            for j in 0..10 {
-                self.image_content[i][j] = match steps % 4 {
-                    1 => tmp_image[9 - j][i],
-                    2 => tmp_image[9 - i][9 - j],
-                    3 => tmp_image[j][9 - i],
-                    _ => tmp_image[i][j],
-                };
-            }
+        for _ in 0..steps % 4 {
+            self.image_content = self.image_content.rotate_clockwise();
         }
         self.update_edges();
     }
@@ -190,8 +192,8 @@ impl Tile {
 }
 
 impl TilePuzzle {
-    pub fn load(filename: &str) -> AOCResult<TilePuzzle> {
-        let tiles = Self::load_tiles(filename);
+    pub fn load(input: impl Into<Input>) -> AOCResult<TilePuzzle> {
+        let tiles = Self::load_tiles(input)?;
         let associations = Self::tile_associations(&tiles);
         Ok(TilePuzzle {
             tiles,
@@ -207,7 +209,7 @@ impl TilePuzzle {
         let mut solved_tiles = HashMap::new();
         solved_tiles.insert(origin_tile_id, (0, 0));
 
-        let mut neighbors = self.associations.remove(&origin_tile_id)?;
+        let mut neighbors = self.associations.remove(&origin_tile_id).ok_or_none()?;
         neighbors.sort();
         for neighbor_id in neighbors.into_iter() {
             self.re_solve(&mut solved_tiles, origin_tile_id, neighbor_id)?;
@@ -251,7 +253,7 @@ impl TilePuzzle {
     ) -> AOCResult<()> {
         if !solved_tiles.contains_key(&unsolved_id) {
             let transform_info =
-                self.tiles[&unsolved_id].can_transform(&self.tiles[&solved_neighbor])?;
+                self.tiles[&unsolved_id].can_transform(&self.tiles[&solved_neighbor]).ok_or_none()?;
 
             let solved_position = solved_tiles[&solved_neighbor];
             let position = (
@@ -260,9 +262,9 @@ impl TilePuzzle {
             );
 
             solved_tiles.insert(unsolved_id, position);
-            self.tiles.get_mut(&unsolved_id)?.apply(&transform_info);
+            self.tiles.get_mut(&unsolved_id).ok_or_none()?.apply(&transform_info);
 
-            let mut neighbors = self.associations.remove(&unsolved_id)?;
+            let mut neighbors = self.associations.remove(&unsolved_id).ok_or_none()?;
             neighbors.sort();
             for neighbor_id in neighbors.into_iter() {
                 self.re_solve(solved_tiles, unsolved_id, neighbor_id)?;
@@ -285,22 +287,21 @@ impl TilePuzzle {
             .collect::<Vec<_>>()
     }
 
-    fn load_tiles(filename: &str) -> HashMap<usize, Tile> {
+    fn load_tiles(input: impl Into<Input>) -> AOCResult<HashMap<usize, Tile>> {
         let mut tiles = HashMap::new();
-        let mut input = utils::get_input(filename);
-        while let Ok(tile) = Tile::load(&mut input) {
+        let mut input = utils::get_input(input)?;
+        while let Some(mut section) = input.section() {
+            let tile = Tile::load(&mut section)?;
             tiles.insert(tile.id, tile);
         }
-        tiles
+        Ok(tiles)
     }
 
-    fn tile_associations<'a>(tiles: &HashMap<usize, Tile>) -> HashMap<usize, Vec<usize>> {
+    fn tile_associations(tiles: &HashMap<usize, Tile>) -> HashMap<usize, Vec<usize>> {
         let tiles = tiles.values().collect::<Vec<_>>();
         let mut tile_matches = HashMap::new();
-        for i in 0..tiles.len() {
-            let this_tile = tiles[i];
-            for j in i + 1..tiles.len() {
-                let that_tile = tiles[j];
+        for (i, this_tile) in tiles.iter().enumerate() {
+            for that_tile in tiles.iter().skip(i + 1) {
                 if this_tile.can_transform(that_tile).is_some() {
                     tile_matches
                         .entry(this_tile.id)
@@ -318,66 +319,53 @@ impl TilePuzzle {
 }
 
 impl TileSolution {
+    // Width and height of the arrangement, counted in tiles.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
     pub fn image(&self, fullsize: bool) -> TileImage {
-        let mut image_data = Vec::new();
         let num_pixels = if fullsize { 10 } else { 8 };
         let pixel_offset = if fullsize { 0 } else { 1 };
+        let mut pixels = Grid2D::new(self.width * num_pixels, self.height * num_pixels, false);
         for (i, tile) in self.tiles.iter().enumerate() {
+            let (tile_x, tile_y) = ((i % self.width) * num_pixels, (i / self.width) * num_pixels);
             for y in 0..num_pixels {
-                let image_y = y + (i / self.width) * num_pixels;
-                let mut pixels = tile.image_content[y + pixel_offset]
-                    .get(pixel_offset..10 - pixel_offset)
-                    .unwrap()
-                    .iter()
-                    .map(|v| *v)
-                    .collect::<Vec<_>>();
-                if image_y == image_data.len() {
-                    image_data.push(pixels);
-                } else {
-                    image_data[image_y].append(&mut pixels);
+                for x in 0..num_pixels {
+                    pixels[(tile_x + x, tile_y + y)] = tile.image_content[(x + pixel_offset, y + pixel_offset)];
                 }
             }
         }
-        TileImage { pixels: image_data }
+        let monsters = pixels.map(|_| false);
+        TileImage { pixels, monsters }
     }
 }
 
 use std::fmt;
 impl fmt::Display for TileImage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in self.pixels.iter() {
-            write!(
-                f,
-                "{}\n",
-                row.iter()
-                    .map(|v| if *v { '#' } else { '.' })
-                    .collect::<String>()
-            )?;
-        }
-        Ok(())
+        write!(f, "{}", self.pixels)
     }
 }
 
 impl TileImage {
     pub fn rotate_clockwise(&mut self) {
-        let old_image = self.pixels.clone();
-        let num_rows = old_image.len();
-        for (i, row) in self.pixels.iter_mut().enumerate() {
-            for (j, pixel) in row.iter_mut().enumerate() {
-                *pixel = old_image[num_rows - 1 - j][i];
-            }
-        }
+        self.pixels = self.pixels.rotate_clockwise();
+        self.monsters = self.monsters.rotate_clockwise();
     }
 
     pub fn flip_vertical(&mut self) {
-        let num_rows = self.pixels.len();
-        for i in 0..num_rows / 2 {
-            for j in 0..self.pixels[i].len() {
-                let old_pixel = self.pixels[i][j];
-                self.pixels[i][j] = self.pixels[num_rows - 1 - i][j];
-                self.pixels[num_rows - 1 - i][j] = old_pixel;
-            }
+        self.pixels = self.pixels.flip_vertical();
+        self.monsters = self.monsters.flip_vertical();
+    }
+
+    // The image as the puzzle draws it, with monsters as 'O'.
+    pub fn chart(&self) -> Grid2D<char> {
+        let mut chart = self.pixels.map(|pixel| pixel.glyph());
+        for (position, _) in self.monsters.iter().filter(|(_, monster)| **monster) {
+            chart[position] = 'O';
         }
+        chart
     }
 
     pub fn part2(&mut self) -> usize {
@@ -394,22 +382,20 @@ impl TileImage {
             }
         };
 
-        return 0;
+        0
     }
 
     pub fn delete_monsters(&mut self) -> bool {
-        let mut monster_check = Vec::new();
-        "                  # ".chars().enumerate().fold((), |_, (i,c)| if c == '#' {monster_check.push((0,i))} );
-        "#    ##    ##    ###".chars().enumerate().fold((), |_, (i,c)| if c == '#' {monster_check.push((1,i))} );
-        " #  #  #  #  #  #   ".chars().enumerate().fold((), |_, (i,c)| if c == '#' {monster_check.push((2,i))} );
+        let monster_check = sea_monster();
 
         let mut found_monster = false;
-        for i in 0..self.pixels.len()-2 {
-            for j in 0..self.pixels[i].len()-20 {
-                if monster_check.iter().all(|delta| self.pixels[i+delta.0][j+delta.1]) {
+        for i in 0..self.pixels.height()-2 {
+            for j in 0..self.pixels.width()-20 {
+                if monster_check.iter().all(|delta| self.pixels[(j+delta.1, i+delta.0)]) {
                     found_monster = true;
                     for delta in monster_check.iter() {
-                        self.pixels[i+delta.0][j+delta.1] = false;
+                        self.pixels[(j+delta.1, i+delta.0)] = false;
+                        self.monsters[(j+delta.1, i+delta.0)] = true;
                     }
                 }
             }
@@ -419,7 +405,7 @@ impl TileImage {
     }
 
     pub fn count_markers(&self) -> usize {
-        self.pixels.iter().fold(0, |a, row| row.iter().fold(a, |count, pixel| count + if *pixel {1} else {0}))
+        self.pixels.count(|pixel| *pixel)
     }
 }
 
@@ -427,12 +413,163 @@ pub fn part1(puzzle: &TilePuzzle) -> u64 {
     puzzle.corners().iter().fold(1, |a, id| a * (*id as u64))
 }
 
-pub fn day20() {
-    let puzzle = TilePuzzle::load("day20").unwrap();
-    println!("jurassic_jigsaw part 1: {:?}", part1(&puzzle));
+pub struct Day20;
 
-    let solution = puzzle.solve().unwrap();
-    println!("jurassic_jigsaw part 2: {:?}", solution.image(false).part2());
+impl Solution for Day20 {
+    type Model = TilePuzzle;
+    type Part1 = u64;
+    type Part2 = usize;
+
+    const DAY: u8 = 20;
+    const NAME: &'static str = "jurassic_jigsaw";
+
+    fn parse(input: &Input) -> AOCResult<TilePuzzle> {
+        TilePuzzle::load(input)
+    }
+
+    fn part1(puzzle: &TilePuzzle) -> AOCResult<u64> {
+        Ok(part1(puzzle))
+    }
+
+    fn part2(puzzle: &TilePuzzle) -> AOCResult<usize> {
+        Ok(puzzle.clone().solve()?.image(false).part2())
+    }
+
+    // The assembled image with the sea monsters picked out.
+    fn picture(puzzle: &TilePuzzle, part: u8) -> AOCResult<Option<String>> {
+        if part != 2 {
+            return Ok(None);
+        }
+        let mut image = puzzle.clone().solve()?.image(false);
+        image.part2();
+        Ok(Some(image.chart().to_string()))
+    }
+
+    // `size` tiles per side, from 3 to 12, 12 by default like the real input.
+    fn generate(rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Some(random_tiles(rng, size.unwrap_or(12)))
+    }
+}
+
+// Gives the n x n tiles of `image` borders that all differ, either way
+// round, or returns false if it ran out of them and needs another go. Even
+// odds for the corners spread the borders evenly over corner pairs; two
+// dark corners only have room for 120 different borders.
+fn random_borders(rng: &mut Rng, image: &mut Grid2D<bool>, n: usize) -> bool {
+    for position in (0..=n).flat_map(|r| (0..=n).map(move |c| (9 * c, 9 * r))) {
+        image[position] = rng.chance(0.5);
+    }
+
+    // Every border from corner to corner, across then down.
+    let borders = (0..=n)
+        .flat_map(|r| (0..n).map(move |c| (9 * c, 9 * r, (1, 0))))
+        .chain((0..n).flat_map(|r| (0..=n).map(move |c| (9 * c, 9 * r, (0, 1)))))
+        .collect::<Vec<_>>();
+    let mut used = std::collections::HashSet::new();
+    for (x, y, (dx, dy)) in borders {
+        let mut middles = (0..256u16).collect::<Vec<_>>();
+        rng.shuffle(&mut middles);
+        let border = |middle: u16| {
+            let bit = |i: usize| if i == 0 || i == 9 { image[(x + dx * i, y + dy * i)] } else { middle >> (i - 1) & 1 == 1 };
+            (0..10).map(bit).collect::<Vec<_>>()
+        };
+        let unique = |bits: &Vec<bool>| {
+            let reversed = bits.iter().rev().copied().collect::<Vec<_>>();
+            reversed != *bits && !used.contains(bits) && !used.contains(&reversed)
+        };
+        let bits = match middles.into_iter().map(border).find(unique) {
+            Some(bits) => bits,
+            None => return false,
+        };
+        for (i, bit) in bits.iter().enumerate() {
+            image[(x + dx * i, y + dy * i)] = *bit;
+        }
+        used.insert(bits);
+    }
+    true
+}
+
+// The trimmed image: `monsters` sea monsters facing the same way and clear
+// of the edges. Overlapping monsters can make up another one facing some
+// other way, which would throw part 2 off, so that gives None to try again.
+fn random_sea(rng: &mut Rng, side: usize, monsters: usize) -> Option<Grid2D<bool>> {
+    let mut pixels = Grid2D::new(side, side, false);
+    for position in pixels.positions().collect::<Vec<_>>() {
+        pixels[position] = rng.chance(0.2);
+    }
+    for _ in 0..monsters {
+        let (x, y) = (1 + rng.below(side - 21), 1 + rng.below(side - 4));
+        for (row, column) in sea_monster() {
+            pixels[(x + column, y + row)] = true;
+        }
+    }
+
+    if monster_ways(&pixels) == 1 {
+        Some(pixels)
+    } else {
+        None
+    }
+}
+
+// How many of the 8 ways round `pixels` has sea monsters in. Finding them
+// deletes them, so each way round gets a fresh copy.
+fn monster_ways(pixels: &Grid2D<bool>) -> usize {
+    let mut ways = 0;
+    let mut turned = pixels.clone();
+    for flip in 0..2 {
+        if flip == 1 {
+            turned = turned.flip_vertical();
+        }
+        for _ in 0..4 {
+            let monsters = turned.map(|_| false);
+            ways += (TileImage { pixels: turned.clone(), monsters }).delete_monsters() as usize;
+            turned = turned.rotate_clockwise();
+        }
+    }
+    ways
+}
+
+// A random image with sea monsters in it, cut into tiles that are then
+// turned, flipped and shuffled. Neighbouring tiles share their border, and
+// every border is different from every other one either way round, which
+// only leaves room for about 12x12 tiles.
+pub fn random_tiles(rng: &mut Rng, size: usize) -> String {
+    let n = size.clamp(3, 12);
+    let inner = loop {
+        if let Some(inner) = random_sea(rng, 8 * n, n * n / 4) {
+            break inner;
+        }
+    };
+    let side = 9 * n + 1;
+    let mut image = Grid2D::new(side, side, false);
+    for position in image.positions().collect::<Vec<_>>() {
+        image[position] = rng.chance(0.2);
+    }
+    while !random_borders(rng, &mut image, n) {}
+    // The trimmed image goes inside the borders.
+    for (x, y) in inner.positions().collect::<Vec<_>>() {
+        image[(9 * (x / 8) + 1 + x % 8, 9 * (y / 8) + 1 + y % 8)] = inner[(x, y)];
+    }
+
+    let mut ids = (1000..10000).collect::<Vec<_>>();
+    rng.shuffle(&mut ids);
+    let mut tiles = (0..n * n)
+        .zip(ids)
+        .map(|(i, id)| {
+            let (x, y) = (9 * (i % n), 9 * (i / n));
+            let rows = (0..10).map(|dy| (0..10).map(|dx| image[(x + dx, y + dy)]).collect()).collect();
+            let mut tile = Grid2D::from_rows(rows).expect("tiles are square");
+            for _ in 0..rng.below(4) {
+                tile = tile.rotate_clockwise();
+            }
+            if rng.chance(0.5) {
+                tile = tile.flip_vertical();
+            }
+            format!("Tile {}:\n{}", id, tile)
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut tiles);
+    tiles.join("\n")
 }
 
 #[cfg(test)]
@@ -445,13 +582,25 @@ mod tests {
 
         let solution = puzzle.solve().unwrap();
 
-        println!("{}", solution.image(false));
-
         assert_eq!(solution.image(false).part2(), 273);
     }
 
-    /*pub fn test_day20_rotate() {
+    #[test]
+    pub fn test_day20_chart() {
+        let mut image = TilePuzzle::load("test_day20").unwrap().solve().unwrap().image(false);
+        image.part2();
+        assert_eq!(image.chart().count(|c| *c == 'O'), 30);
+    }
+
+    // An image's rows, for comparing against the puzzle's drawings.
+    fn rows(image: &TileImage) -> Vec<String> {
+        image.to_string().lines().map(str::to_owned).collect()
+    }
+
+    #[test]
+    pub fn test_day20_rotate() {
         let tile_input = vec![
+            "Tile 1:".to_owned(),
             ".##.###..#".to_owned(),
             "#......##.".to_owned(),
             "..#.#.....".to_owned(),
@@ -464,7 +613,7 @@ mod tests {
             "..###..##.".to_owned(),
         ];
 
-        let mut tile = Tile::load(&mut tile_input.into_iter()).unwrap();
+        let mut tile = Tile::load(&mut Lines::new("tile", tile_input)).unwrap();
         let transform = TileTransform {
             flipped_edge: None,
             rotate_steps: 1,
@@ -479,7 +628,7 @@ mod tests {
         };
 
         assert_eq!(
-            image.image(true),
+            rows(&image.image(true)),
             vec![
                 "..#..##.#.",
                 "...#.....#",
@@ -493,9 +642,9 @@ mod tests {
                 "...####..#",
             ]
         );
-    }*/
+    }
 
-    /*#[test]
+    #[test]
     pub fn test_day20_basic() {
         let mut puzzle = TilePuzzle::load("test_day20_basic").unwrap();
 
@@ -511,7 +660,7 @@ mod tests {
             tiles: vec![puzzle.tiles[&2].clone()],
         };
         assert_eq!(
-            transformed_image.image(true),
+            rows(&transformed_image.image(true)),
             vec![
                 "#.........",
                 "..........",
@@ -526,7 +675,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            transformed_image.image(false),
+            rows(&transformed_image.image(false)),
             vec![
                 "........", "........", "........", "........", "........", "........", "........",
                 "########",
@@ -535,7 +684,7 @@ mod tests {
 
         let solution = puzzle.solve().unwrap();
         assert_eq!(
-            solution.image(true),
+            rows(&solution.image(true)),
             vec![
                 "###########.........",
                 ".#..................",
@@ -550,7 +699,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            solution.image(false),
+            rows(&solution.image(false)),
             vec![
                 "#...............",
                 "................",
@@ -562,5 +711,23 @@ mod tests {
                 "##......########"
             ]
         );
-    }*/
+    }
+
+    #[test]
+    pub fn test_day20_generate() {
+        for seed in 0..3 {
+            let puzzle = TilePuzzle::load(Input::Text(random_tiles(&mut Rng::new(seed), 4))).unwrap();
+            assert_eq!(puzzle.tiles.len(), 16);
+
+            // Borders only ever match the tile next to them, so corners have
+            // two neighbours, edges three and the rest four.
+            let mut neighbours = puzzle.associations.values().map(Vec::len).collect::<Vec<_>>();
+            neighbours.sort_unstable();
+            assert_eq!(neighbours, [vec![2; 4], vec![3; 8], vec![4; 4]].concat());
+
+            // The monsters only face one way, so part 2 finds all of them.
+            let image = puzzle.solve().unwrap().image(false);
+            assert_eq!(monster_ways(&image.pixels), 1);
+        }
+    }
 }
@@ -1,67 +1,47 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{solution::Solution, utils};
 use std::collections::HashMap;
 use std::collections::HashSet;
-use utils::{AOCError, AOCResult};
-
-lazy_static! {
-    static ref WORD_RE: Regex = regex!(r"([a-z]+)");
-}
+use utils::{AOCResult, Input, Parser};
 
+#[derive(Clone)]
 pub struct AllergenInfo {
     ingredients: HashMap<String, usize>,
     candidates_per_allergen: HashMap<String, HashSet<String>>,
 }
 
 impl AllergenInfo {
-    pub fn load(filename: &str) -> AllergenInfo {
-        let input = utils::get_input(filename);
-        let mut info = AllergenInfo {
+    pub fn load(input: impl Into<Input>) -> AOCResult<AllergenInfo> {
+        let mut input = utils::get_input(input)?;
+        let info = AllergenInfo {
             ingredients: HashMap::new(),
             candidates_per_allergen: HashMap::new(),
         };
 
-        input
-            .map(|line| {
-                let mut local_ingredients = HashSet::new();
-                let mut local_allergens = HashSet::new();
-                let mut processing_allergens = false;
-                for capture in WORD_RE.captures_iter(&line) {
-                    let word = &capture[1];
-                    if word == "contains" {
-                        processing_allergens = true;
-                        continue;
-                    }
-                    if processing_allergens {
-                        local_allergens.insert(word.to_owned());
-                    } else {
-                        local_ingredients.insert(word.to_owned());
-                    }
-                }
-
-                (local_ingredients, local_allergens)
-            })
+        // "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)"
+        Ok(input
+            .parse_each(|p| {
+                let ingredients = p.before(" (contains ", |p| p.list(" ", Parser::word))?;
+                let allergens = p.list(", ", Parser::word)?;
+                p.literal(")")?;
+                Ok((
+                    ingredients.into_iter().map(str::to_owned).collect::<HashSet<_>>(),
+                    allergens.into_iter().map(str::to_owned).collect::<HashSet<_>>(),
+                ))
+            })?
+            .into_iter()
             .fold(info, |mut i, (ingredients, allergens)| {
                 for ingredient in ingredients.iter() {
                     *i.ingredients.entry(ingredient.clone()).or_insert(0) += 1;
                 }
                 for allergen in allergens.into_iter() {
-                    if !i.candidates_per_allergen.contains_key(&allergen) {
-                        i.candidates_per_allergen
-                            .insert(allergen, ingredients.clone());
-                    } else {
-                        i.candidates_per_allergen
-                            .get_mut(&allergen)
-                            .unwrap()
-                            .drain_filter(|v| !ingredients.contains(v)).for_each(drop);
-                    }
+                    i.candidates_per_allergen
+                        .entry(allergen)
+                        .and_modify(|candidates| candidates.retain(|v| ingredients.contains(v)))
+                        .or_insert_with(|| ingredients.clone());
                 }
 
                 i
-            })
+            }))
     }
 
     pub fn part1(&self) -> usize {
@@ -72,26 +52,23 @@ impl AllergenInfo {
 
     fn non_allergens(&self) -> HashSet<&String> {
         let maybe_allergen = self.candidates_per_allergen
-            .iter()
-            .flat_map(|(_, ingredients)| ingredients)
+            .values()
+            .flatten()
             .collect::<HashSet<_>>();
 
-        self.ingredients.iter().filter_map(|(i, _)|
-            if !maybe_allergen.contains(i) {
-                Some(i)
-            } else {
-                None
-            }
-        ).collect()
+        self.ingredients
+            .keys()
+            .filter(|i| !maybe_allergen.contains(i))
+            .collect()
     }
 
     pub fn solve(&mut self) -> HashMap<String, String> {
         let mut allergens = HashMap::new();
-        while self.candidates_per_allergen.len() != 0 {
+        while !self.candidates_per_allergen.is_empty() {
             for (_, candidates) in self.candidates_per_allergen.iter_mut() {
-                candidates.drain_filter(|v| allergens.contains_key(v)).for_each(drop);
+                candidates.retain(|v| !allergens.contains_key(v));
             }
-            for (allergen, candidates) in self.candidates_per_allergen.drain_filter(|_, candidates| candidates.len() == 1) {
+            for (allergen, candidates) in self.candidates_per_allergen.extract_if(|_, candidates| candidates.len() == 1) {
                 allergens.insert(candidates.into_iter().next().unwrap(), allergen);
             }
         }
@@ -101,7 +78,7 @@ impl AllergenInfo {
 
     pub fn part2(&mut self) -> String {
         let allergens = self.solve();
-        let mut danger_list = allergens.into_iter().map(|(ingredient, allergen)| (ingredient, allergen)).collect::<Vec<_>>();
+        let mut danger_list = allergens.into_iter().collect::<Vec<_>>();
         danger_list.sort_by(|this, that| {
             this.1.partial_cmp(&that.1).unwrap()
         });
@@ -109,10 +86,27 @@ impl AllergenInfo {
     }
 }
 
-pub fn day21() {
-    let mut info = AllergenInfo::load("day21");
-    println!("allergen_assessment part 1: {:?}", info.part1());
-    println!("allergen_assessment part 2: {:?}", info.part2());
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Model = AllergenInfo;
+    type Part1 = usize;
+    type Part2 = String;
+
+    const DAY: u8 = 21;
+    const NAME: &'static str = "allergen_assessment";
+
+    fn parse(input: &Input) -> AOCResult<AllergenInfo> {
+        AllergenInfo::load(input)
+    }
+
+    fn part1(info: &AllergenInfo) -> AOCResult<usize> {
+        Ok(info.part1())
+    }
+
+    fn part2(info: &AllergenInfo) -> AOCResult<String> {
+        Ok(info.clone().part2())
+    }
 }
 
 #[cfg(test)]
@@ -121,7 +115,7 @@ mod tests {
 
     #[test]
     pub fn test_day21() {
-        let mut info = AllergenInfo::load("test_day21");
+        let mut info = AllergenInfo::load("test_day21").unwrap();
         assert_eq!(info.part1(), 5,);
         assert_eq!(info.part2(), "mxmxvkd,sqjhc,fvjkl");
     }
@@ -1,12 +1,11 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, utils};
-use utils::{AOCResult, AOCError};
+use crate::{aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input, Rng};
 use std::collections::HashSet;
 
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
+#[derive(Clone)]
 pub struct CombatGame {
     p1: Vec<usize>,
     p2: Vec<usize>,
@@ -30,7 +29,7 @@ impl CombatGame {
         }
     }
 
-    pub fn load(filename: &str, recurse: bool) -> AOCResult<CombatGame> {
+    pub fn load(input: impl Into<Input>, recurse: bool) -> AOCResult<CombatGame> {
         let mut game = CombatGame {
             p1: Vec::new(),
             p2: Vec::new(),
@@ -38,20 +37,22 @@ impl CombatGame {
             recurse,
         };
 
-        let mut player_ref = &mut game.p1;
-        let input = utils::get_input(filename);
-        for line in input {
-            match line.as_str() {
-                "Player 1:" => {
-                    player_ref = &mut game.p1;
-                },
-                "Player 2:" => {
-                    player_ref = &mut game.p2;
-                },
-                "" => {},
-                _ => {
-                    player_ref.push(line.parse::<usize>()?)
-                }
+        let mut input = utils::get_input(input)?;
+        while let Some(mut section) = input.section() {
+            let player = section.parse_next(|p| {
+                p.literal("Player ")?;
+                let player = p.word()?;
+                p.literal(":")?;
+                Ok(match player {
+                    "1" => Player::P1,
+                    "2" => Player::P2,
+                    _ => aocbail!(at p.span(player); "Unknown player {}", player),
+                })
+            })?;
+            let deck = section.parse_each(|p| p.int())?;
+            match player {
+                Player::P1 => game.p1 = deck,
+                Player::P2 => game.p2 = deck,
             }
         }
 
@@ -59,9 +60,9 @@ impl CombatGame {
     }
 
     pub fn step(&mut self) -> Option<Player> {
-        if self.p1.len() == 0 {
+        if self.p1.is_empty() {
             return Some(Player::P2);
-        } else if self.p2.len() == 0 {
+        } else if self.p2.is_empty() {
             return Some(Player::P1);
         }
 
@@ -101,20 +102,61 @@ impl CombatGame {
 
     pub fn play(&mut self) -> (Player, usize) {
         loop {
-            match self.step() {
-                Some(s) => {return (s, self.score(s));},
-                None => ()
+            if let Some(s) = self.step() {
+                return (s, self.score(s));
             }
         }
     }
 }
 
-pub fn day22() {
-    let mut game = CombatGame::load("day22", false).unwrap();
-    println!("crab_combat part 1: {:?}", game.play());
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Model = CombatGame;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 22;
+    const NAME: &'static str = "crab_combat";
+
+    fn parse(input: &Input) -> AOCResult<CombatGame> {
+        CombatGame::load(input, false)
+    }
+
+    fn part1(game: &CombatGame) -> AOCResult<usize> {
+        Ok(game.clone().play().1)
+    }
+
+    fn part2(game: &CombatGame) -> AOCResult<usize> {
+        let mut game = game.clone();
+        game.recurse = true;
+        Ok(game.play().1)
+    }
+
+    // `size` cards each, 25 by default like the real input.
+    fn generate(rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Some(random_deal(rng, size.unwrap_or(25).max(1)))
+    }
+}
 
-    let mut game = CombatGame::load("day22", true).unwrap();
-    println!("crab_combat part 2: {:?}", game.play());
+// Shuffles cards 1 to 2n into two decks of n, dealing again until a plain
+// game of combat ends without repeating itself, as it does for real inputs.
+pub fn random_deal(rng: &mut Rng, n: usize) -> String {
+    let mut cards = (1..=2 * n).collect::<Vec<_>>();
+    loop {
+        rng.shuffle(&mut cards);
+        let mut game = CombatGame {
+            p1: cards[..n].to_vec(),
+            p2: cards[n..].to_vec(),
+            cache: HashSet::new(),
+            recurse: false,
+        };
+        let winner = game.play().0;
+        if winner == Player::P2 || game.p2.is_empty() {
+            let deck = |cards: &[usize]| cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("\n");
+            return format!("Player 1:\n{}\n\nPlayer 2:\n{}", deck(&cards[..n]), deck(&cards[n..]));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -135,4 +177,23 @@ mod tests {
             (Player::P2, 291)
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn test_day22_generate() {
+        for seed in 0..5 {
+            let game = CombatGame::load(Input::Text(random_deal(&mut Rng::new(seed), 10)), false).unwrap();
+            assert_eq!((game.p1.len(), game.p2.len()), (10, 10));
+
+            // Every card from 1 to 20 is dealt exactly once.
+            let mut cards = [game.p1.clone(), game.p2.clone()].concat();
+            cards.sort_unstable();
+            assert_eq!(cards, (1..=20).collect::<Vec<_>>());
+
+            // A plain game ends with someone holding every card, rather than
+            // being called off for repeating itself.
+            let mut plain = game.clone();
+            plain.play();
+            assert!(plain.p1.is_empty() || plain.p2.is_empty());
+        }
+    }
+}
@@ -1,4 +1,6 @@
-#![allow(dead_code)]
+use crate::{animate::{Animation, Take}, aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input};
+use std::fmt;
 
 pub struct CrabCups<const N: usize> {
     cups: Vec<usize>,
@@ -7,6 +9,7 @@ pub struct CrabCups<const N: usize> {
 }
 
 impl <const N: usize> CrabCups <{N}> {
+    // `labels` go first, and have to be 1 to labels.len() in some order.
     pub fn load(labels: &[usize]) -> CrabCups<{N}> {
         let mut cups = vec![0; N];
         for i in 0..N {
@@ -67,7 +70,17 @@ impl <const N: usize> CrabCups <{N}> {
         cups
     }
 
-    pub fn into_iter(mut self) -> impl Iterator<Item = Vec<usize>> {
+    pub fn labels_after_one(&self) -> String {
+        let mut labels = String::new();
+        let mut next_cup = self.cups[0];
+        while next_cup != 0 {
+            labels.push_str(&(next_cup + 1).to_string());
+            next_cup = self.cups[next_cup];
+        }
+        labels
+    }
+
+    pub fn rounds(mut self) -> impl Iterator<Item = Vec<usize>> {
         std::iter::from_fn(move || {
             self.step();
             Some(self.to_labels())
@@ -75,20 +88,87 @@ impl <const N: usize> CrabCups <{N}> {
     }
 }
 
-pub fn day23() {
-    let input = [2,1,9,3,4,7,8,6,5];
-    let cups: CrabCups<9> = CrabCups::load(&input);
-    println!("crab_cups part 1: {:?}", cups.into_iter().nth(99));
+impl<const N: usize> Animation for CrabCups<{ N }> {
+    fn step(&mut self) -> bool {
+        CrabCups::step(self);
+        true
+    }
+}
 
-    let mut cups: CrabCups<1000000> = CrabCups::load(&input);
-    for _ in 0..10000000 {
-        cups.step();
+// The circle as the puzzle prints it, with the current cup in brackets.
+impl<const N: usize> fmt::Display for CrabCups<{ N }> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {}\ncups:", self.steps + 1)?;
+        for (i, label) in self.to_labels().iter().enumerate() {
+            if i == self.steps % N {
+                write!(f, " ({})", label)?;
+            } else {
+                write!(f, " {}", label)?;
+            }
+        }
+        writeln!(f)
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Model = Vec<usize>;
+    type Part1 = String;
+    type Part2 = u64;
+
+    const DAY: u8 = 23;
+    const NAME: &'static str = "crab_cups";
+
+    // The cups' labels, which have to be 1 to 9 in some order.
+    fn parse(input: &Input) -> AOCResult<Vec<usize>> {
+        utils::get_input(input)?.parse_next(|p| {
+            let line = p.rest();
+            let mut labels = Vec::new();
+            while !p.is_empty() {
+                let rest = p.rest();
+                let c = p.char()?;
+                let label = &rest[..c.len_utf8()];
+                match c.to_digit(10).map(|d| d as usize) {
+                    Some(d) if labels.contains(&d) => aocbail!(at p.span(label); "Cup {} appears twice", d),
+                    Some(d) if d >= 1 => labels.push(d),
+                    _ => aocbail!(at p.span(label); "Unknown cup label '{}', expected 1 to 9", c),
+                }
+            }
+            if labels.len() != 9 {
+                aocbail!(at p.span(line); "Expected 9 cups, found {}", labels.len());
+            }
+            Ok(labels)
+        })
+    }
+
+    fn part1(labels: &Vec<usize>) -> AOCResult<String> {
+        let mut cups: CrabCups<9> = CrabCups::load(labels);
+        for _ in 0..100 {
+            cups.step();
+        }
+        Ok(cups.labels_after_one())
     }
-    let i1 = cups.cups[0] as u64;
-    let i2 = cups.cups[i1 as usize] as u64;
 
-    println!("crab_cups part 2: {:?}", (i1+1) * (i2+1));
+    fn part2(labels: &Vec<usize>) -> AOCResult<u64> {
+        let mut cups: CrabCups<1000000> = CrabCups::load(labels);
+        for _ in 0..10000000 {
+            cups.step();
+        }
+        let i1 = cups.cups[0] as u64;
+        let i2 = cups.cups[i1 as usize] as u64;
+
+        Ok((i1+1) * (i2+1))
+    }
 
+    // Only the small game; a million cups don't fit on a terminal.
+    fn animate(labels: &Vec<usize>, part: u8) -> Option<Box<dyn Animation>> {
+        if part == 1 {
+            Some(Box::new(Take::new(CrabCups::<9>::load(labels), 100)))
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -118,9 +198,9 @@ mod tests {
             vec![3,2,8,9,1,5,4,6,7]
         );
 
-        let mut i = cups.into_iter();
+        let mut i = cups.rounds();
         assert_eq!(
-            i.nth(0),
+            i.next(),
             Some(vec![3,2,5,4,6,7,8,9,1])
         );
 
@@ -149,4 +229,23 @@ mod tests {
         );
 
     }
+
+    #[test]
+    pub fn test_day23_display() {
+        let mut cups: CrabCups<9> = CrabCups::load(&[3, 8, 9, 1, 2, 5, 4, 6, 7]);
+        assert_eq!(cups.to_string(), "move 1\ncups: (3) 8 9 1 2 5 4 6 7\n");
+        cups.step();
+        assert_eq!(cups.to_string(), "move 2\ncups: 3 (2) 8 9 1 5 4 6 7\n");
+    }
+
+    #[test]
+    pub fn test_day23_parse() {
+        let parse = |text: &str| Day23::parse(&Input::Text(text.to_owned()));
+        assert_eq!(parse("389125467").unwrap(), vec![3, 8, 9, 1, 2, 5, 4, 6, 7]);
+        let error = |text: &str| parse(text).err().unwrap().to_string();
+        assert!(error("389125460").starts_with("Unknown cup label '0', expected 1 to 9"));
+        assert!(error("38912546x").starts_with("Unknown cup label 'x', expected 1 to 9"));
+        assert!(error("389125463").starts_with("Cup 3 appears twice"));
+        assert!(error("38912546").starts_with("Expected 9 cups, found 8"));
+    }
 }
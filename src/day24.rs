@@ -1,9 +1,8 @@
-#![allow(dead_code)]
+use crate::{animate::{Animation, Take}, solution::Solution, utils};
+use utils::{AOCResult, Automaton, Input, Rng, Sparse};
+use std::collections::HashSet;
+use std::fmt;
 
-use crate::utils;
-use std::collections::{HashMap, HashSet};
-
-use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 #[derive(EnumIter)]
@@ -47,14 +46,15 @@ impl Direction {
     }
 }
 
+#[derive(Clone)]
 pub struct TileFloor {
-    black_tiles: HashSet<(i32, i32)>,
+    black_tiles: Sparse<(i32, i32)>,
 }
 
 impl TileFloor {
-    pub fn load(filename: &str) -> TileFloor {
+    pub fn load(input: impl Into<Input>) -> AOCResult<TileFloor> {
         let mut black_tiles = HashSet::new();
-        let input = utils::get_input(filename);
+        let input = utils::get_input(input)?;
 
         for line in input {
             let tile = TileFloor::parse_tile(&line);
@@ -63,7 +63,11 @@ impl TileFloor {
             }
         }
 
-        TileFloor { black_tiles }
+        // A tile flips to black with exactly 2 black neighbours, and stays
+        // black with 1 or 2.
+        Ok(TileFloor {
+            black_tiles: Sparse::hex(black_tiles, |black, count| count == 2 || (black && count == 1)),
+        })
     }
 
     pub fn parse_tile(line: &str) -> (i32, i32) {
@@ -81,40 +85,108 @@ impl TileFloor {
     }
 
     pub fn step(&mut self) {
-        let mut neighbor_count: HashMap<(i32, i32), usize> = HashMap::new();
-        for tile in self.black_tiles.iter() {
-            neighbor_count.entry(*tile).or_insert(0);
-
-            for neighbor in Direction::iter().map(|v| {
-                let delta = v.delta();
-                (tile.0 + delta.0, tile.1 + delta.1)
-            }) {
-                *neighbor_count.entry(neighbor).or_insert(0) += 1;
-            }
-        }
+        self.black_tiles.step();
+    }
+}
 
-        self.black_tiles = neighbor_count
-            .into_iter()
-            .filter_map(|(tile, count)|
-                if count == 2 || (self.black_tiles.contains(&tile) && count == 1) {
-                    Some(tile)
-                } else {
-                    None
-                }
-            )
-            .collect::<HashSet<_>>();
+impl Animation for TileFloor {
+    fn step(&mut self) -> bool {
+        TileFloor::step(self);
+        true
     }
 }
 
-pub fn day24() {
-    let mut tiles = TileFloor::load("day24");
-    println!("lobby_layout part 1: {:?}", tiles.num_black());
+// Draws the floor with north up. Tiles sit one column apart in alternate
+// rows, so every other char on a row is padding between hexes.
+impl fmt::Display for TileFloor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tiles = self.black_tiles.live();
+        let (xs, ys) = (tiles.iter().map(|t| t.0), tiles.iter().map(|t| t.1));
+        let (x0, x1) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+        let (y0, y1) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+        for y in (y0..=y1).rev() {
+            let row = (x0..=x1).map(|x| match ((x + y) % 2 == 0, tiles.contains(&(x, y))) {
+                (true, true) => '#',
+                (true, false) => '.',
+                _ => ' ',
+            });
+            writeln!(f, "{}", row.collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
 
-    for _ in 0..100 {
-        tiles.step();
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Model = TileFloor;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 24;
+    const NAME: &'static str = "lobby_layout";
+
+    fn parse(input: &Input) -> AOCResult<TileFloor> {
+        TileFloor::load(input)
+    }
+
+    fn part1(floor: &TileFloor) -> AOCResult<usize> {
+        Ok(floor.num_black())
+    }
+
+    fn part2(floor: &TileFloor) -> AOCResult<usize> {
+        let mut floor = floor.clone();
+        floor.black_tiles.run_n(100);
+        Ok(floor.num_black())
+    }
+
+    fn animate(floor: &TileFloor, part: u8) -> Option<Box<dyn Animation>> {
+        if part == 2 {
+            Some(Box::new(Take::new(floor.clone(), 100)))
+        } else {
+            None
+        }
+    }
+
+    // The floor as laid, then after 100 days.
+    fn picture(floor: &TileFloor, part: u8) -> AOCResult<Option<String>> {
+        let mut floor = floor.clone();
+        if part == 2 {
+            floor.black_tiles.run_n(100);
+        }
+        Ok(Some(floor.to_string()))
+    }
+
+    // `size` lines, 320 by default like the real input.
+    fn generate(rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Some(random_paths(rng, size.unwrap_or(320)))
     }
-    println!("lobby_layout part 2: {:?}", tiles.num_black());
+}
 
+// Paths to tiles up to 20 steps out, padded with steps that cancel out to
+// 10 to 20 steps and shuffled. Some take an earlier path's steps in another
+// order, which ends on the same tile and flips it back.
+pub fn random_paths(rng: &mut Rng, lines: usize) -> String {
+    // Neighbouring directions are next to each other, opposites 3 apart.
+    const STEPS: [&str; 6] = ["e", "se", "sw", "w", "nw", "ne"];
+    let mut paths: Vec<Vec<&str>> = Vec::with_capacity(lines);
+    for _ in 0..lines {
+        let mut path = if !paths.is_empty() && rng.chance(0.1) {
+            rng.pick(&paths).clone()
+        } else {
+            let (k, a, b) = (rng.below(6), rng.range(1..=10) as usize, rng.range(0..=10) as usize);
+            let mut path = std::iter::repeat_n(STEPS[k], a).chain(std::iter::repeat_n(STEPS[(k + 1) % 6], b)).collect::<Vec<_>>();
+            let steps = rng.range(10..=20) as usize;
+            while path.len() + 2 <= steps {
+                let d = rng.below(6);
+                path.extend([STEPS[d], STEPS[(d + 3) % 6]].iter());
+            }
+            path
+        };
+        rng.shuffle(&mut path);
+        paths.push(path);
+    }
+    paths.iter().map(|path| path.concat()).collect::<Vec<_>>().join("\n")
 }
 
 #[cfg(test)]
@@ -125,7 +197,7 @@ mod tests {
     pub fn test_day24() {
         assert_eq!((0, 0), TileFloor::parse_tile("nwwswee"));
 
-        let mut tiles = TileFloor::load("test_day24");
+        let mut tiles = TileFloor::load("test_day24").unwrap();
         assert_eq!(tiles.num_black(), 10);
 
         tiles.step();
@@ -139,4 +211,32 @@ mod tests {
         }
         assert_eq!(tiles.num_black(), 2208);
     }
+
+    #[test]
+    pub fn test_day24_display() {
+        let floor = TileFloor {
+            black_tiles: Sparse::hex([(0, 0), (1, 1), (-2, 0)].iter().copied().collect(), |_, _| false),
+        };
+        assert_eq!(floor.to_string(), " . #\n# #\n");
+    }
+
+    #[test]
+    pub fn test_day24_generate() {
+        for seed in 0..5 {
+            let text = random_paths(&mut Rng::new(seed), 100);
+            assert_eq!(text.lines().count(), 100);
+
+            // Every line is nothing but steps, so none of it is skipped.
+            for line in text.lines() {
+                let mut rest = line;
+                while !rest.is_empty() {
+                    rest = ["e", "se", "sw", "w", "nw", "ne"].iter().find_map(|step| rest.strip_prefix(step)).unwrap();
+                }
+            }
+
+            // Some paths end on the same tile, flipping it back.
+            let floor = TileFloor::load(Input::Text(text)).unwrap();
+            assert!(floor.num_black() < 100);
+        }
+    }
 }
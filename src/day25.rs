@@ -1,7 +1,5 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, utils};
-use utils::{AOCResult, AOCError};
+use crate::{solution::Solution, utils};
+use utils::{AOCResult, Input};
 
 pub fn solve_loop_size(subject: u64, target: u64) -> u64 {
     let mut loop_size = 0;
@@ -15,17 +13,35 @@ pub fn solve_loop_size(subject: u64, target: u64) -> u64 {
 
 pub fn transform(subject: u64, loop_size: u64) -> u64 {
     let mut value = 1;
-    for i in 0..loop_size {
+    for _ in 0..loop_size {
         value = (value * subject) % 20201227;
     }
     value
 }
 
-pub fn day25() {
-    let card_loop = solve_loop_size(7, 13233401);
-    let door_loop = solve_loop_size(7, 6552760);
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Model = (u64, u64);
+    type Part1 = u64;
+    type Part2 = ();
+
+    const DAY: u8 = 25;
+    const NAME: &'static str = "combo_breaker";
 
-    println!("combo_breaker part 1: {:?} {:?}", transform(6552760, card_loop), transform(13233401, door_loop));
+    fn parse(input: &Input) -> AOCResult<(u64, u64)> {
+        let mut input = utils::get_input(input)?;
+        Ok((input.parse_next(|p| p.int())?, input.parse_next(|p| p.int())?))
+    }
+
+    fn part1((card_key, door_key): &(u64, u64)) -> AOCResult<u64> {
+        Ok(transform(*door_key, solve_loop_size(7, *card_key)))
+    }
+
+    // Day 25 only has the one part.
+    fn part2(_keys: &(u64, u64)) -> AOCResult<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -52,4 +68,11 @@ mod tests {
             14897079
         );
     }
+
+    #[test]
+    pub fn test_day25_no_part2() {
+        let day = crate::runner::solution(25).unwrap();
+        assert!(!day.has_part(2));
+        assert_eq!(day.part2(&(5764801u64, 17807724u64)).unwrap(), crate::solution::Answer::None);
+    }
 }
@@ -1,74 +1,67 @@
-#![allow(dead_code)]
-
-use crate::{utils, utils::AOCError};
+use crate::{solution::Solution, utils};
+use utils::{pixel, AOCResult, Grid2D, Input};
 
+// A slope down the map, which repeats endlessly to the right.
 pub struct Trajectory {
     right: usize,
     down: usize,
-    tree_count: u64,
-    right_offset: usize,
-    to_skip: usize,
 }
 
 impl Trajectory {
     fn new(right: usize, down: usize) -> Self {
-        Trajectory {
-            right,
-            down,
-            tree_count: 0,
-            right_offset: 0,
-            to_skip: 0,
-        }
-    }
-
-    fn step(&mut self, line: &str) -> Result<(), AOCError> {
-        if self.to_skip == 0 {
-            let target = line.chars().nth(self.right_offset % line.len())?;
-            if target == '#' {
-                self.tree_count += 1;
-            }
-            self.right_offset = (self.right_offset + self.right) % line.len();
-            self.to_skip = self.down;
-        }
-        self.to_skip -= 1;
-        Ok(())
+        Trajectory { right, down }
     }
 
-    fn count(&self) -> u64 {
-        self.tree_count
+    fn count(&self, map: &Grid2D<bool>) -> u64 {
+        (0..map.height())
+            .step_by(self.down)
+            .enumerate()
+            .filter(|(step, y)| *map.get_wrapping(((step * self.right) as isize, *y as isize)))
+            .count() as u64
     }
 }
 
-pub fn day3() {
-    let input = utils::get_input("day3");
-    println!(
-        "toboggan trajectory part 1: {}",
-        toboggan_trajectory_p1(input).unwrap()
-    );
-
-    let input = utils::get_input("day3");
-    println!(
-        "toboggan trajectory part 2: {}",
-        toboggan_trajectory_p2(input).unwrap()
-    );
+pub fn load(input: impl Into<Input>) -> AOCResult<Grid2D<bool>> {
+    Grid2D::parse(&mut utils::get_input(input)?, pixel)
 }
 
-pub fn toboggan_trajectory<T: Iterator<Item = String>>(
-    input: T,
-    mut trajectory: Trajectory,
-) -> Result<u64, AOCError> {
-    for line in input {
-        trajectory.step(&line)?;
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Model = Grid2D<bool>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    const DAY: u8 = 3;
+    const NAME: &'static str = "toboggan_trajectory";
+
+    fn parse(input: &Input) -> AOCResult<Grid2D<bool>> {
+        load(input)
+    }
+
+    fn part1(map: &Grid2D<bool>) -> AOCResult<u64> {
+        Ok(toboggan_trajectory_p1(map))
+    }
+
+    fn part2(map: &Grid2D<bool>) -> AOCResult<u64> {
+        Ok(toboggan_trajectory_p2(map))
+    }
+
+    fn picture(map: &Grid2D<bool>, part: u8) -> AOCResult<Option<String>> {
+        Ok(if part == 1 { Some(map.to_string()) } else { None })
     }
-    Ok(trajectory.count())
 }
 
-pub fn toboggan_trajectory_p1<T: Iterator<Item = String>>(input: T) -> Result<u64, AOCError> {
-    toboggan_trajectory(input, Trajectory::new(3, 1))
+pub fn toboggan_trajectory(map: &Grid2D<bool>, trajectory: Trajectory) -> u64 {
+    trajectory.count(map)
 }
 
-pub fn toboggan_trajectory_p2<T: Iterator<Item = String>>(input: T) -> Result<u64, AOCError> {
-    let mut trajectories = vec![
+pub fn toboggan_trajectory_p1(map: &Grid2D<bool>) -> u64 {
+    toboggan_trajectory(map, Trajectory::new(3, 1))
+}
+
+pub fn toboggan_trajectory_p2(map: &Grid2D<bool>) -> u64 {
+    let trajectories = [
         Trajectory::new(1, 1),
         Trajectory::new(3, 1),
         Trajectory::new(5, 1),
@@ -76,34 +69,16 @@ pub fn toboggan_trajectory_p2<T: Iterator<Item = String>>(input: T) -> Result<u6
         Trajectory::new(1, 2),
     ];
 
-    for line in input {
-        for trajectory in trajectories.iter_mut() {
-            trajectory.step(&line)?;
-        }
-    }
-
-    Ok(trajectories
+    trajectories
         .iter()
-        .fold(1, |a: u64, t: &Trajectory| a * t.count() as u64))
+        .fold(1, |a: u64, t: &Trajectory| a * t.count(map))
 }
 
 #[test]
 fn basic_toboggan_trajectory() {
-    let test_input = utils::get_input("test_day3");
-    assert_eq!(toboggan_trajectory_p1(test_input).unwrap(), 7);
-
-    let test_input = utils::get_input("test_day3");
-    assert_eq!(
-        toboggan_trajectory(test_input, Trajectory::new(1, 2)).unwrap(),
-        2
-    );
-
-    let test_input = utils::get_input("test_day3");
-    assert_eq!(
-        toboggan_trajectory(test_input, Trajectory::new(7, 1)).unwrap(),
-        4
-    );
-
-    let test_input = utils::get_input("test_day3");
-    assert_eq!(toboggan_trajectory_p2(test_input).unwrap(), 336);
+    let map = load("test_day3").unwrap();
+    assert_eq!(toboggan_trajectory_p1(&map), 7);
+    assert_eq!(toboggan_trajectory(&map, Trajectory::new(1, 2)), 2);
+    assert_eq!(toboggan_trajectory(&map, Trajectory::new(7, 1)), 4);
+    assert_eq!(toboggan_trajectory_p2(&map), 336);
 }
@@ -1,22 +1,12 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{aocbail, solution::Solution, utils};
 use std::collections::HashSet;
 use std::str::FromStr;
 use strum::EnumCount;
 use strum_macros::{EnumCount as EnumCountMacro, EnumString};
-use utils::{AOCError, AOCResult};
-
-lazy_static! {
-    static ref YEAR_REGEX: Regex = regex!(r"^(\d\d\d\d)$");
-    static ref HEIGHT_REGEX: Regex = regex!(r"^(\d+)(in|cm)$");
-    static ref HAIR_REGEX: Regex = regex!(r"^#[0-9a-f]{6}$");
-    static ref EYE_REGEX: Regex = regex!(r"^amb|blu|brn|gry|grn|hzl|oth$");
-    static ref COUNTRY_REGEX: Regex = regex!(r"^\d{9}$");
-}
+use utils::{parse_line, record, records, AOCResult, Input, Lines, Span, SpanExt};
 
+// Variants are named after the passport field keys they parse from.
+#[allow(clippy::upper_case_acronyms)]
 #[derive(EnumString, EnumCountMacro, Eq, PartialEq, Hash)]
 #[strum(serialize_all = "snake_case")]
 pub enum PassportKey {
@@ -32,12 +22,19 @@ pub enum PassportKey {
 
 impl PassportKey {
     fn is_valid_year(value: &str, min: usize, max: usize) -> bool {
-        for capture in YEAR_REGEX.captures_iter(value) {
-            if let Ok(year) = capture[1].parse::<usize>() {
-                return year >= min && year <= max;
-            }
+        value.len() == 4 && parse_line(value, |p| p.int()).is_ok_and(|year| (min..=max).contains(&year))
+    }
+
+    fn is_valid_height(value: &str) -> bool {
+        let height = parse_line(value, |p| {
+            let height = p.int::<usize>()?;
+            Ok((height, p.word()?))
+        });
+        match height {
+            Ok((height, "cm")) => (150..=193).contains(&height),
+            Ok((height, "in")) => (59..=76).contains(&height),
+            _ => false,
         }
-        return false;
     }
 
     pub fn is_valid(&self, value: &str) -> bool {
@@ -45,21 +42,12 @@ impl PassportKey {
             PassportKey::BYR => Self::is_valid_year(value, 1920, 2002),
             PassportKey::IYR => Self::is_valid_year(value, 2010, 2020),
             PassportKey::EYR => Self::is_valid_year(value, 2020, 2030),
-            PassportKey::HGT => {
-                for capture in HEIGHT_REGEX.captures_iter(value) {
-                    if let Ok(height) = capture[1].parse::<usize>() {
-                        return if &capture[2] == "cm" {
-                            height >= 150 && height <= 193
-                        } else {
-                            height >= 59 && height <= 76
-                        };
-                    }
-                }
-                return false;
-            }
-            PassportKey::HCL => HAIR_REGEX.is_match(value),
-            PassportKey::ECL => EYE_REGEX.is_match(value),
-            PassportKey::PID => COUNTRY_REGEX.is_match(value),
+            PassportKey::HGT => Self::is_valid_height(value),
+            PassportKey::HCL => value
+                .strip_prefix('#')
+                .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))),
+            PassportKey::ECL => ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&value),
+            PassportKey::PID => value.len() == 9 && value.chars().all(|c| c.is_ascii_digit()),
             PassportKey::CID => true,
         }
     }
@@ -79,23 +67,27 @@ impl Passport {
     }
 
     pub fn parse_entry(&mut self, keyvalue: &str) -> AOCResult<()> {
-        let re = regex::Regex::new(r"^([a-z]+):([a-z0-9#]+)$")?;
-        for capture in re.captures_iter(keyvalue) {
-            let key: PassportKey = PassportKey::from_str(&capture[1])?;
-            if !self.valid_data_only || key.is_valid(&capture[2]) {
-                self.entries.insert(key);
-            }
-            return Ok(());
-        }
-        aocbail!("Unable to parse passport key from {}", keyvalue)
+        let (key, value) = record(keyvalue, ":")?;
+        self.insert(keyvalue, key, value)
     }
 
     pub fn parse_entries(&mut self, input: &str) -> AOCResult<()> {
-        input
-            .split(" ")
-            .map(|entry: &str| self.parse_entry(entry))
-            .collect::<AOCResult<Vec<()>>>()
-            .and(Ok(()))
+        for (key, value) in records(input, ":")? {
+            self.insert(input, key, value)?;
+        }
+        Ok(())
+    }
+
+    // `key` and `value` are slices of `text`, for pointing errors at.
+    fn insert(&mut self, text: &str, key: &str, value: &str) -> AOCResult<()> {
+        let key = match PassportKey::from_str(key) {
+            Ok(key) => key,
+            Err(_) => aocbail!(at Span::new(text, key); "Unknown passport key '{}'", key),
+        };
+        if !self.valid_data_only || key.is_valid(value) {
+            self.entries.insert(key);
+        }
+        Ok(())
     }
 
     pub fn is_valid(&self) -> bool {
@@ -107,34 +99,41 @@ impl Passport {
     }
 }
 
-pub fn day4() {
-    // 230
-    let mut input = utils::get_input("day4");
-    println!(
-        "Passport processing part 1: {}",
-        num_valid_passports(input, false).unwrap()
-    );
-
-    // 156
-    input = utils::get_input("day4");
-    println!(
-        "Passport processing part 2: {}",
-        num_valid_passports(input, true).unwrap()
-    );
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Model = Lines;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 4;
+    const NAME: &'static str = "passport_processing";
+
+    fn parse(input: &Input) -> AOCResult<Lines> {
+        utils::get_input(input)
+    }
+
+    fn part1(batch: &Lines) -> AOCResult<usize> {
+        num_valid_passports(batch.clone(), false)
+    }
+
+    fn part2(batch: &Lines) -> AOCResult<usize> {
+        num_valid_passports(batch.clone(), true)
+    }
 }
 
-pub fn num_valid_passports(
-    mut input: impl Iterator<Item = String>,
-    validate_data: bool,
-) -> AOCResult<usize> {
+pub fn num_valid_passports(mut input: Lines, validate_data: bool) -> AOCResult<usize> {
     let mut count: usize = 0;
     let mut passport = Passport::new(validate_data);
     while let Some(line) = input.next() {
-        if line.len() == 0 {
+        if line.is_empty() {
             count += if passport.is_valid() { 1 } else { 0 };
             passport = Passport::new(validate_data);
         } else {
-            passport.parse_entries(&line)?;
+            passport
+                .parse_entries(&line)
+                .at_line(input.line())
+                .in_input(input.name())?;
         }
     }
 
@@ -144,7 +143,7 @@ pub fn num_valid_passports(
 
 #[test]
 pub fn basic_passport_processing() {
-    let test_input = utils::get_input("test_day4");
+    let test_input = utils::get_input("test_day4").unwrap();
     assert_eq!(num_valid_passports(test_input, false).unwrap(), 2);
 
     assert!(PassportKey::BYR.is_valid("2002"));
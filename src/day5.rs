@@ -1,7 +1,5 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, utils};
-use utils::{AOCError, AOCResult};
+use crate::{aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input, OptionExt};
 
 pub struct SeatRange {
     min: usize,
@@ -10,7 +8,7 @@ pub struct SeatRange {
 
 impl SeatRange {
     pub fn narrow(&mut self, take_upper: bool) -> Option<usize> {
-        let delta = (self.max - self.min + 1) / 2;
+        let delta = (self.max - self.min).div_ceil(2);
         if take_upper {
             self.min += delta;
         } else {
@@ -24,16 +22,27 @@ impl SeatRange {
     }
 }
 
-pub fn day5() {
-    let input = utils::get_input("day5");
-    println!(
-        "binary_boarding part 1: {}", find_hipri_seat(input).unwrap()
-    );
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Model = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 5;
+    const NAME: &'static str = "binary_boarding";
+
+    fn parse(input: &Input) -> AOCResult<Vec<String>> {
+        Ok(utils::get_input(input)?.collect())
+    }
 
-    let input = utils::get_input("day5");
-    println!(
-        "binary_boarding part 2: {}", find_your_seat(input).unwrap()
-    );
+    fn part1(passes: &Vec<String>) -> AOCResult<usize> {
+        find_hipri_seat(passes.iter().cloned())
+    }
+
+    fn part2(passes: &Vec<String>) -> AOCResult<usize> {
+        find_your_seat(passes.iter().cloned())
+    }
 }
 
 pub fn find_your_seat(input: impl Iterator<Item=String>) -> AOCResult<usize> {
@@ -46,10 +55,8 @@ pub fn find_your_seat(input: impl Iterator<Item=String>) -> AOCResult<usize> {
         seats[seat] = 1;
     }
 
-    for i in (min+1)..seats.len() {
-        if seats[i] == 0 {
-            return Ok(i);
-        }
+    if let Some(i) = (min+1..seats.len()).find(|i| seats[*i] == 0) {
+        return Ok(i);
     }
 
     aocbail!("Unable to find seat!");
@@ -88,7 +95,7 @@ pub fn find_seat(boarding_pass: &str) -> AOCResult<usize> {
             }
         }
     }
-    Ok(row? * 8 + column?)
+    Ok(row.ok_or_none()? * 8 + column.ok_or_none()?)
 }
 
 
@@ -1,61 +1,56 @@
-#![allow(dead_code)]
+use crate::{solution::Solution, utils};
+use utils::{AOCResult, Input};
 
-use crate::{utils};
-use utils::{AOCResult};
+// Each group's answers, one bitfield of questions per person.
+pub type Groups = Vec<Vec<u32>>;
 
-pub fn day6() {
-    let input = utils::get_input("day6");
-    println!("custom_customs part 1: {}", sum_answers(input).unwrap());
-
-    let input = utils::get_input("day6");
-    println!(
-        "custom_customs part 2: {}",
-        sum_intersection_answers(input).unwrap()
-    );
+pub fn load(input: impl Into<Input>) -> AOCResult<Groups> {
+    let mut input = utils::get_input(input)?;
+    let mut groups = Vec::new();
+    while let Some(mut group) = input.section() {
+        groups.push(group.parse_each(|p| {
+            let answers = p.take_while(|c| c.is_ascii_lowercase());
+            Ok(answers_to_bitfield(answers))
+        })?);
+    }
+    Ok(groups)
 }
 
-pub fn sum_answers(input: impl Iterator<Item = String>) -> AOCResult<usize> {
-    let mut answers: [u8; 26] = [0; 26];
-    let mut answer_count: usize = 0;
-    let mut sum = 0;
-    for line in input {
-        if line.len() == 0 {
-            sum += answer_count;
-            answer_count = 0;
-            answers = [0; 26];
-        } else {
-            for answer in line.chars() {
-                let i = answer as usize - 'a' as usize;
-                answer_count += (answers[i] ^ 1) as usize;
-                answers[i] = 1;
-            }
-        }
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Model = Groups;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const DAY: u8 = 6;
+    const NAME: &'static str = "custom_customs";
+
+    fn parse(input: &Input) -> AOCResult<Groups> {
+        load(input)
     }
 
-    Ok(sum + answer_count)
-}
+    fn part1(groups: &Groups) -> AOCResult<u32> {
+        Ok(sum_answers(groups))
+    }
 
-pub fn sum_intersection_answers(input: impl Iterator<Item = String>) -> AOCResult<u32> {
-    let mut answers: u32 = 0;
-    let mut count = 0;
-    let mut first_entry = true;
-    for line in input {
-        answers = if line.len() == 0 {
-            count += bitfield_count(answers);
-            first_entry = true;
-            0
-        } else {
-            let temp_answers = answers_to_bitfield(&line);
-            if first_entry {
-                first_entry = false;
-                temp_answers
-            } else {
-                answers & temp_answers
-            }
-        }
+    fn part2(groups: &Groups) -> AOCResult<u32> {
+        Ok(sum_intersection_answers(groups))
     }
+}
 
-    Ok(count + bitfield_count(answers))
+pub fn sum_answers(groups: &[Vec<u32>]) -> u32 {
+    groups
+        .iter()
+        .map(|group| bitfield_count(group.iter().fold(0, |acc, answers| acc | answers)))
+        .sum()
+}
+
+pub fn sum_intersection_answers(groups: &[Vec<u32>]) -> u32 {
+    groups
+        .iter()
+        .map(|group| bitfield_count(group.iter().fold(!0, |acc, answers| acc & answers)))
+        .sum()
 }
 
 pub fn answers_to_bitfield(answers: &str) -> u32 {
@@ -75,9 +70,7 @@ pub fn bitfield_count(bitfield: u32) -> u32 {
 
 #[test]
 pub fn basic_custom_customs() {
-    let input = utils::get_input("test_day6");
-    assert_eq!(sum_answers(input).unwrap(), 11);
-
-    let input = utils::get_input("test_day6");
-    assert_eq!(sum_intersection_answers(input).unwrap(), 6);
+    let groups = load("test_day6").unwrap();
+    assert_eq!(sum_answers(&groups), 11);
+    assert_eq!(sum_intersection_answers(&groups), 6);
 }
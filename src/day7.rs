@@ -1,40 +1,103 @@
-#![allow(dead_code)]
-
-use crate::{regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
+use crate::{solution::Solution, utils};
 use std::collections::{HashMap, HashSet};
-use utils::AOCResult;
-
-lazy_static! {
-    static ref BAG_REGEX: Regex = regex!(r"([0-9])* ?([a-z]+ [a-z]+) bags?");
-}
+use utils::{parse_line, AOCResult, Input, Lines, OptionExt, Parser, Rng};
 
 pub type BagID = usize;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct BagRules {
     registry: HashMap<String, BagID>,
     contained_by: HashMap<BagID, HashMap<BagID, usize>>,
     contains: HashMap<BagID, HashMap<BagID, usize>>,
 }
 
-pub fn day7() {
-    let input = utils::get_input("day7");
-    let mut rules = BagRules::new();
-    println!(
-        "handy_haversacks part 1: {}",
-        rules
-            .process(input)
-            .unwrap()
-            .container_count("shiny gold")
-            .unwrap()
-    );
+pub struct Day7;
 
-    println!(
-        "handy_haversacks part 2: {}",
-        rules.contained_count("shiny gold").unwrap()
-    );
+impl Solution for Day7 {
+    type Model = BagRules;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = 7;
+    const NAME: &'static str = "handy_haversacks";
+
+    fn parse(input: &Input) -> AOCResult<BagRules> {
+        let mut rules = BagRules::new();
+        rules.process(utils::get_input(input)?)?;
+        Ok(rules)
+    }
+
+    fn part1(rules: &BagRules) -> AOCResult<usize> {
+        rules.container_count("shiny gold")
+    }
+
+    fn part2(rules: &BagRules) -> AOCResult<usize> {
+        rules.contained_count("shiny gold")
+    }
+
+    // `size` bags, 594 by default like the real input.
+    fn generate(rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Some(random_rules(rng, size.unwrap_or(594)))
+    }
+}
+
+const ADJECTIVES: &[&str] = &[
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored", "muted", "pale", "plaid",
+    "posh", "shiny", "striped", "vibrant", "wavy",
+];
+
+const COLOURS: &[&str] = &[
+    "aqua", "beige", "black", "blue", "bronze", "brown", "chartreuse", "coral", "crimson", "cyan", "fuchsia", "gold",
+    "gray", "green", "indigo", "lavender", "lime", "magenta", "maroon", "olive", "orange", "plum", "purple", "red",
+    "salmon", "silver", "tan", "teal", "tomato", "turquoise", "violet", "white", "yellow",
+];
+
+// Rules for up to 594 bags, shiny gold among them. Bags are spread over
+// levels and only hold bags from the next two, so there are no cycles,
+// a fifth of the level above shiny gold holds it directly, and the few
+// levels below it keep its count small.
+pub fn random_rules(rng: &mut Rng, bags: usize) -> String {
+    const LEVELS: usize = 12;
+    const GOLD: usize = LEVELS - 5;
+    let mut names = ADJECTIVES
+        .iter()
+        .flat_map(|adjective| COLOURS.iter().map(move |colour| format!("{} {}", adjective, colour)))
+        .filter(|name| name != "shiny gold")
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut names);
+    names.truncate(bags.clamp(1, names.len() + 1) - 1);
+
+    let mut levels = names.into_iter().map(|name| (rng.below(LEVELS), name)).collect::<Vec<_>>();
+    levels.push((GOLD, "shiny gold".to_owned()));
+    levels.sort();
+
+    let mut rules = levels
+        .iter()
+        .map(|(level, name)| {
+            let deeper = &levels[levels.partition_point(|(l, _)| l <= level)..levels.partition_point(|(l, _)| *l <= level + 2)];
+            let mut contents = Vec::new();
+            if level + 1 == GOLD && rng.chance(0.2) {
+                contents.push((rng.range(1..=5), "shiny gold"));
+            }
+            for _ in 0..if deeper.is_empty() { 0 } else { rng.below(5) } {
+                let (_, bag) = rng.pick(deeper);
+                if !contents.iter().any(|(_, b)| b == bag) {
+                    contents.push((rng.range(1..=5), bag.as_str()));
+                }
+            }
+            let contents = contents
+                .iter()
+                .map(|(count, bag)| format!("{} {} bag{}", count, bag, if *count == 1 { "" } else { "s" }))
+                .collect::<Vec<_>>();
+            if contents.is_empty() {
+                format!("{} bags contain no other bags.", name)
+            } else {
+                format!("{} bags contain {}.", name, contents.join(", "))
+            }
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut rules);
+    rules.join("\n") + "\n"
 }
 
 impl BagRules {
@@ -51,33 +114,45 @@ impl BagRules {
         *self.registry.entry(bag_type.to_string()).or_insert(count)
     }
 
-    pub fn process(&mut self, input: impl Iterator<Item = String>) -> AOCResult<&mut Self> {
-        for line in input {
-            self.process_line(&line)?;
-        }
+    pub fn process(&mut self, mut input: Lines) -> AOCResult<&mut Self> {
+        input.parse_each(|p| self.rule(p))?;
         Ok(self)
     }
 
     pub fn process_line(&mut self, line: &str) -> AOCResult<&mut Self> {
-        let mut captures = BAG_REGEX.captures_iter(line);
-        let container_bag_type = self.register(&captures.next()?[2]);
-
-        for capture in captures {
-            let count = capture
-                .get(1)
-                .map_or(Ok(0), |m| m.as_str().parse::<usize>())?;
-            let contained_bag_type = self.register(&capture[2]);
+        parse_line(line, |p| self.rule(p))?;
+        Ok(self)
+    }
+
+    // "light red bags contain 1 bright white bag, 2 muted yellow bags."
+    fn rule(&mut self, p: &mut Parser) -> AOCResult<()> {
+        let container_bag_type = self.register(p.until(" bags contain ")?);
+        if p.eat("no other bags") {
+            return p.literal(".");
+        }
+
+        let contents = p.list(", ", |p| {
+            let count = p.int::<usize>()?;
+            p.literal(" ")?;
+            let bag_type = p.until(" bag")?;
+            p.eat("s");
+            Ok((count, bag_type))
+        })?;
+        p.literal(".")?;
+
+        for (count, bag_type) in contents {
+            let contained_bag_type = self.register(bag_type);
             self.contained_by
                 .entry(contained_bag_type)
-                .or_insert(HashMap::new())
+                .or_default()
                 .insert(container_bag_type, count);
             self.contains
                 .entry(container_bag_type)
-                .or_insert(HashMap::new())
+                .or_default()
                 .insert(contained_bag_type, count);
         }
 
-        Ok(self)
+        Ok(())
     }
 
     fn gather_containers(&self, bag_id: &BagID, seen_types: &mut HashSet<BagID>) {
@@ -107,14 +182,14 @@ impl BagRules {
     }
 
     pub fn container_count(&self, bag_type: &str) -> AOCResult<usize> {
-        let bag_id = self.registry.get(bag_type)?;
+        let bag_id = self.registry.get(bag_type).ok_or_none()?;
         let mut containers: HashSet<BagID> = HashSet::new();
-        self.gather_containers(&bag_id, &mut containers);
+        self.gather_containers(bag_id, &mut containers);
         Ok(containers.len())
     }
 
     pub fn contained_count(&self, bag_type: &str) -> AOCResult<usize> {
-        let bag_id = self.registry.get(bag_type)?;
+        let bag_id = self.registry.get(bag_type).ok_or_none()?;
         let mut cached_count: HashMap<BagID, usize> = HashMap::new();
         Ok(self.gather_contained(bag_id, &mut cached_count))
     }
@@ -122,7 +197,7 @@ impl BagRules {
 
 #[test]
 pub fn basic_handy_haversacks() {
-    let input = utils::get_input("test_day7");
+    let input = utils::get_input("test_day7").unwrap();
     let mut rules = BagRules::new();
     assert_eq!(
         rules
@@ -135,3 +210,34 @@ pub fn basic_handy_haversacks() {
 
     assert_eq!(rules.contained_count("shiny gold").unwrap(), 32);
 }
+
+#[test]
+pub fn test_day7_generate() {
+    for seed in 0..5 {
+        let text = random_rules(&mut Rng::new(seed), 200);
+        let mut rules = BagRules::new();
+        rules.process(utils::get_input(Input::Text(text.clone())).unwrap()).unwrap();
+
+        // One rule per bag, and no bag is held without a rule of its own.
+        let named = text.lines().map(|line| line.split(" bags contain ").next().unwrap()).collect::<HashSet<_>>();
+        assert_eq!((text.lines().count(), named.len(), rules.registry.len()), (200, 200, 200));
+
+        // Emptying bags from the inside out gets through all of them, so
+        // none ends up inside itself.
+        let mut holds = rules.registry.values().map(|id| (*id, rules.contains.get(id).map_or(0, HashMap::len))).collect::<HashMap<_, _>>();
+        let mut empty = holds.iter().filter(|(_, n)| **n == 0).map(|(id, _)| *id).collect::<Vec<_>>();
+        let mut emptied = 0;
+        while let Some(id) = empty.pop() {
+            emptied += 1;
+            for container in rules.contained_by.get(&id).into_iter().flat_map(HashMap::keys) {
+                let n = holds.get_mut(container).unwrap();
+                *n -= 1;
+                if *n == 0 {
+                    empty.push(*container);
+                }
+            }
+        }
+        assert_eq!(emptied, 200);
+        assert!(rules.container_count("shiny gold").unwrap() > 0);
+    }
+}
@@ -1,14 +1,7 @@
-#![allow(dead_code)]
-
-use crate::{aocbail, regex, utils};
-use lazy_static::lazy_static;
-use regex::Regex;
-use utils::{AOCResult, AOCError};
-
-lazy_static! {
-    static ref CMD_REGEX: Regex = regex!(r"([a-z]+) \+?(-?[0-9]+)");
-}
+use crate::{aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input, Lines, Rng};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug)]
 pub enum CMD {
     Acc(i32),
@@ -22,7 +15,7 @@ pub struct ProgramState {
     acc: i32,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Program {
     commands: Vec<CMD>,
 }
@@ -45,18 +38,18 @@ impl ProgramState {
 }
 
 impl Program {
-    pub fn load(input: impl Iterator<Item = String>) -> AOCResult<Program> {
-        let mut commands = Vec::new();
-        for line in input {
-            let capture = CMD_REGEX.captures_iter(&line).next()?;
-            let value = capture[2].parse::<i32>()?;
-            commands.push(match &capture[1] {
+    pub fn load(mut input: Lines) -> AOCResult<Program> {
+        let commands = input.parse_each(|p| {
+            let command = p.word()?;
+            p.literal(" ")?;
+            let value = p.int()?;
+            Ok(match command {
                 "acc" => CMD::Acc(value),
                 "jmp" => CMD::Jmp(value),
                 "nop" => CMD::Nop(value),
-                _ => aocbail!("Unable to parse command")
-            });
-        }
+                _ => aocbail!(at p.span(command); "Unknown command '{}'", command),
+            })
+        })?;
         Ok(Program { commands })
     }
 
@@ -113,16 +106,78 @@ impl Program {
     }
 }
 
-pub fn day8() {
-    let input = utils::get_input("day8");
-    let mut program = Program::load(input).unwrap();
-    println!("handheld_halting part 1: {}", program.run_until_halt());
-    println!("handheld_halting part 2: {}", program.fix_and_run().unwrap());
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Model = Program;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const DAY: u8 = 8;
+    const NAME: &'static str = "handheld_halting";
+
+    fn parse(input: &Input) -> AOCResult<Program> {
+        Program::load(utils::get_input(input)?)
+    }
+
+    fn part1(program: &Program) -> AOCResult<i32> {
+        Ok(program.run_until_halt())
+    }
+
+    fn part2(program: &Program) -> AOCResult<i32> {
+        program.clone().fix_and_run()
+    }
+
+    // `size` instructions, 600 by default like the real input.
+    fn generate(rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Some(random_program(rng, size.unwrap_or(600)))
+    }
+}
+
+// A looping program that swapping one jmp for a nop fixes, and no other
+// swap does. Everything before the broken jmp only jumps within that
+// stretch, so swapping any earlier instruction still loops; after it,
+// jumps only go forward to the end.
+pub fn random_program(rng: &mut Rng, size: usize) -> String {
+    let n = size.max(1);
+    let broken = (n * 2 / 3).min(n - 1);
+    // An offset from `ip` that lands within 0..=end.
+    let within = |rng: &mut Rng, ip: usize, end: usize| rng.range(-(ip as i64)..=(end - ip) as i64);
+
+    // The path the fixed program takes up to the broken jmp.
+    let mut commands = vec![None; n];
+    let mut path = vec![0];
+    while let Some(&ip) = path.last().filter(|ip| **ip < broken) {
+        let next = if rng.chance(0.2) { (ip + rng.below(20) + 2).min(broken) } else { ip + 1 };
+        commands[ip] = Some(if next > ip + 1 {
+            ("jmp", (next - ip) as i64)
+        } else if rng.chance(0.8) {
+            ("acc", rng.range(-50..=50))
+        } else {
+            ("nop", within(rng, ip, broken))
+        });
+        path.push(next);
+    }
+    commands[broken] = Some(("jmp", *rng.pick(&path) as i64 - broken as i64));
+
+    for (ip, command) in commands.iter_mut().enumerate().filter(|(_, command)| command.is_none()) {
+        *command = Some(match rng.below(3) {
+            0 => ("acc", rng.range(-50..=50)),
+            _ if ip < broken => (*rng.pick(&["jmp", "nop"]), within(rng, ip, broken)),
+            1 => ("nop", rng.range(-(n as i64)..=n as i64)),
+            _ => ("jmp", rng.range(1..=(n - ip).min(20) as i64)),
+        });
+    }
+    commands
+        .iter()
+        .flatten()
+        .map(|(command, value)| format!("{} {:+}\n", command, value))
+        .collect()
 }
 
 #[test]
 pub fn basic_handheld_halting() {
-    let input = utils::get_input("test_day8");
+    let input = utils::get_input("test_day8").unwrap();
     let mut program = Program::load(input).unwrap();
     assert_eq!(
         program.run_until_halt(),
@@ -134,3 +189,28 @@ pub fn basic_handheld_halting() {
         8
     );
 }
+
+#[test]
+pub fn test_day8_generate() {
+    for seed in 0..5 {
+        let text = random_program(&mut Rng::new(seed), 300);
+        let mut program = Program::load(utils::get_input(Input::Text(text)).unwrap()).unwrap();
+        let n = program.commands.len();
+        assert_eq!(n, 300);
+
+        // It loops as it is, and swapping exactly one jmp or nop fixes it.
+        let ends = |program: &Program| program.run(ProgramState::default(), vec![false; n]).ip == n;
+        assert!(!ends(&program));
+        let fixes = (0..n)
+            .filter(|ip| {
+                if !program.toggle(*ip) {
+                    return false;
+                }
+                let fixed = ends(&program);
+                program.toggle(*ip);
+                fixed
+            })
+            .count();
+        assert_eq!(fixes, 1);
+    }
+}
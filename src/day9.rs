@@ -1,21 +1,37 @@
-#![allow(dead_code)]
+use crate::{aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input, OptionExt};
 
-use crate::{aocbail, utils};
-use utils::{AOCResult, AOCError};
+pub struct Day9;
 
-pub fn day9() {
-    let input = utils::get_input("day9");
-    let bad_value = find_bad_value(input, 25).unwrap();
-    println!("encoding_error part 1: {}", bad_value.1);
+impl Solution for Day9 {
+    type Model = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
 
-    let input = utils::get_input("day9");
-    println!("encoding_error part 2: {}", find_sum(input, bad_value.0).unwrap());
+    const DAY: u8 = 9;
+    const NAME: &'static str = "encoding_error";
+
+    fn parse(input: &Input) -> AOCResult<Vec<i64>> {
+        load(input)
+    }
+
+    fn part1(data: &Vec<i64>) -> AOCResult<i64> {
+        Ok(find_bad_value(data, 25)?.1)
+    }
+
+    fn part2(data: &Vec<i64>) -> AOCResult<i64> {
+        let bad_value = find_bad_value(data, 25)?;
+        find_sum(data, bad_value.0)
+    }
+}
+
+pub fn load(input: impl Into<Input>) -> AOCResult<Vec<i64>> {
+    utils::get_input(input)?.parse_each(|p| p.int())
 }
 
-pub fn find_bad_value(input: impl Iterator<Item = String>, cache_size: usize) -> AOCResult<(usize, i32)> {
-    let mut cache: Vec<i32> = Vec::with_capacity(cache_size);
-    'outer: for (i, line) in input.enumerate() {
-        let value = line.parse::<i32>()?;
+pub fn find_bad_value(input: &[i64], cache_size: usize) -> AOCResult<(usize, i64)> {
+    let mut cache: Vec<i64> = Vec::with_capacity(cache_size);
+    'outer: for (i, value) in input.iter().copied().enumerate() {
         if i < cache_size {
             cache.push(value);
             continue;
@@ -37,12 +53,8 @@ pub fn find_bad_value(input: impl Iterator<Item = String>, cache_size: usize) ->
     aocbail!("Unable to find illegal entry");
 }
 
-pub fn find_sum(mut input: impl Iterator<Item = String>, target_id: usize) -> AOCResult<i32> {
-    let mut values: Vec<i32> = Vec::new();
-    for _ in 0..=target_id {
-        let value = input.next()?;
-        values.push(value.parse::<i32>()?);
-    }
+pub fn find_sum(input: &[i64], target_id: usize) -> AOCResult<i64> {
+    let values = input.get(..=target_id).ok_or_none()?;
 
     let target = values[target_id];
     let mut end = 0;
@@ -62,13 +74,8 @@ pub fn find_sum(mut input: impl Iterator<Item = String>, target_id: usize) -> AO
             running_sum += values[end];
         }
 
-        let mut min = values[start];
-        let mut max = values[start];
-        for i in start..=end {
-            min = std::cmp::min(min, values[i]);
-            max = std::cmp::max(max, values[i]);
-        }
-        return Ok(min + max);
+        let range = &values[start..=end];
+        return Ok(range.iter().min().ok_or_none()? + range.iter().max().ok_or_none()?);
     }
 
     aocbail!("Something horrible happened");
@@ -76,16 +83,15 @@ pub fn find_sum(mut input: impl Iterator<Item = String>, target_id: usize) -> AO
 
 #[test]
 pub fn encoding_error() {
-    let input = utils::get_input("test_day9");
-    let bad_value = find_bad_value(input, 5).unwrap();
+    let input = load("test_day9").unwrap();
+    let bad_value = find_bad_value(&input, 5).unwrap();
     assert_eq!(
         bad_value.1,
         127
     );
 
-    let input = utils::get_input("test_day9");
     assert_eq!(
-        find_sum(input, bad_value.0).unwrap(),
+        find_sum(&input, bad_value.0).unwrap(),
         62
     );
 }
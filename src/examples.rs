@@ -0,0 +1,95 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+use crate::utils::AOCResult;
+use crate::{aocbail, regex};
+
+lazy_static! {
+    static ref EXAMPLE_RE: Regex = regex!(r"(?s)<pre><code>(.*?)</code></pre>");
+    static ref EMPHASIS_RE: Regex = regex!(r"(?s)<em>(.*?)</em>");
+    static ref TAG_RE: Regex = regex!(r"<[^>]*>");
+    static ref NUMBER_RE: Regex = regex!(r"^-?[0-9]+$");
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn text_of(html: &str) -> String {
+    decode_entities(&TAG_RE.replace_all(html, ""))
+}
+
+// Every <pre><code> block on the page, with markup and entities removed.
+pub fn extract_examples(html: &str) -> Vec<String> {
+    EXAMPLE_RE
+        .captures_iter(html)
+        .map(|block| text_of(&block[1]).trim_end_matches('\n').to_owned())
+        .filter(|example| !example.is_empty())
+        .collect()
+}
+
+// Emphasised numbers, in page order. Puzzle text highlights the example
+// answers this way, alongside a few intermediate values.
+pub fn candidate_answers(html: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    for emphasis in EMPHASIS_RE.captures_iter(html) {
+        let text = text_of(&emphasis[1]).trim().to_owned();
+        if NUMBER_RE.is_match(&text) && !candidates.contains(&text) {
+            candidates.push(text);
+        }
+    }
+    candidates
+}
+
+// The k-th example for a day, following the test_dayN, test_dayN_2, ... naming.
+pub fn example_name(day: u8, k: usize) -> String {
+    match k {
+        1 => format!("test_day{}", day),
+        _ => format!("test_day{}_{}", day, k),
+    }
+}
+
+// Writes examples into the input directory. Empty placeholders such as the
+// ones new-day creates are replaced, anything else needs `force`.
+pub fn write_examples(dir: &Path, day: u8, examples: &[String], force: bool) -> AOCResult<Vec<PathBuf>> {
+    let paths = (1..=examples.len())
+        .map(|k| dir.join(example_name(day, k)))
+        .collect::<Vec<_>>();
+    for path in paths.iter() {
+        if !force && std::fs::metadata(path).is_ok_and(|m| m.len() > 0) {
+            aocbail!("{} already exists, pass --force to overwrite it", path.display());
+        }
+    }
+
+    std::fs::create_dir_all(dir)?;
+    for (path, example) in paths.iter().zip(examples) {
+        std::fs::write(path, example)?;
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::examples::*;
+
+    #[test]
+    pub fn test_examples() {
+        let html = "<article><p>For example:</p>\n<pre><code>0: 4 1 5\n4: &quot;a&quot;\n\n<em>ababbb</em>\n</code></pre>\
+            <p>Here, <code><em>2</em></code> messages match, out of <em>5</em> (or <em>2</em>).</p>\
+            <pre><code>a &lt; b &amp;&amp; c\n</code></pre><p><em>Every</em> rule</p></article>";
+
+        assert_eq!(extract_examples(html), vec!["0: 4 1 5\n4: \"a\"\n\nababbb", "a < b && c"]);
+        assert_eq!(candidate_answers(html), vec!["2", "5"]);
+
+        let dir = crate::utils::test_dir("examples");
+        let paths = write_examples(&dir, 19, &extract_examples(html), false).unwrap();
+        assert_eq!(paths[1], dir.join("test_day19_2"));
+        assert_eq!(std::fs::read_to_string(&paths[1]).unwrap(), "a < b && c");
+        assert!(write_examples(&dir, 19, &extract_examples(html), false).is_err());
+    }
+}
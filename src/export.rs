@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::aocbail;
+use crate::runner::{self, RunOptions};
+use crate::utils::{AOCError, AOCResult, Image, Palette};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+
+    pub fn encode(self, image: &Image) -> Vec<u8> {
+        match self {
+            ImageFormat::Png => image.png(),
+            ImageFormat::Ppm => image.ppm(),
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = AOCError;
+
+    fn from_str(s: &str) -> AOCResult<ImageFormat> {
+        Ok(match s {
+            "png" => ImageFormat::Png,
+            "ppm" => ImageFormat::Ppm,
+            _ => aocbail!("Unknown image format '{}', expected png or ppm", s),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub format: ImageFormat,
+    // Pixels per side of each cell.
+    pub cell: usize,
+    pub palette: Palette,
+    pub dir: PathBuf,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            format: ImageFormat::Png,
+            cell: 4,
+            palette: Palette::default(),
+            dir: PathBuf::from("."),
+        }
+    }
+}
+
+// Paints the picture of every requested part of `day` that has one into
+// `<dir>/dayN-partP.<format>`, returning the files written.
+pub fn run(day: u8, options: &RunOptions, export: &ExportOptions) -> AOCResult<Vec<PathBuf>> {
+    let solution = runner::solution(day)?;
+    let model = solution.parse(&options.input(solution))?;
+    let mut written = Vec::new();
+    for part in options.parts() {
+        if let Some(picture) = solution.picture(part, model.as_ref())? {
+            let image = Image::draw(&picture, export.cell, &export.palette);
+            let path = export.dir.join(format!("day{}-part{}.{}", day, part, export.format.extension()));
+            std::fs::write(&path, export.format.encode(&image))?;
+            written.push(path);
+        }
+    }
+    if written.is_empty() {
+        aocbail!("{} has nothing to export", solution.name());
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::*;
+    use crate::utils::Input;
+
+    #[test]
+    pub fn test_export() {
+        let dir = crate::utils::test_dir("export");
+        let options = RunOptions {
+            part: None,
+            input: Some(Input::from("test_day3")),
+            ..RunOptions::default()
+        };
+        let export = ExportOptions {
+            format: ImageFormat::Ppm,
+            cell: 2,
+            dir: dir.to_path_buf(),
+            ..ExportOptions::default()
+        };
+        let written = run(3, &options, &export).unwrap();
+        assert_eq!(written, vec![dir.join("day3-part1.ppm")]);
+        assert!(std::fs::read(&written[0]).unwrap().starts_with(b"P6\n22 22\n255\n"));
+        let options = RunOptions {
+            part: None,
+            input: Some(Input::from("test_day6")),
+            ..RunOptions::default()
+        };
+        let e = run(6, &options, &export).err().unwrap();
+        assert_eq!(e.to_string(), "custom_customs has nothing to export");
+    }
+}
@@ -0,0 +1,42 @@
+use crate::aocbail;
+use crate::runner;
+use crate::solution::Answer;
+use crate::utils::{AOCResult, Input, Rng};
+
+// A random input for `day`. The same seed and size always give the same
+// input, so a failing one can be reproduced from just those.
+pub fn input(day: u8, seed: u64, size: Option<usize>) -> AOCResult<String> {
+    let solution = runner::solution(day)?;
+    match solution.generate(&mut Rng::new(seed), size) {
+        Some(input) => Ok(input),
+        None => aocbail!("{} has no input generator", solution.name()),
+    }
+}
+
+// Generates an input and solves both parts of it.
+pub fn solve(day: u8, seed: u64, size: Option<usize>) -> AOCResult<[Answer; 2]> {
+    let solution = runner::solution(day)?;
+    let model = solution.parse(&Input::Text(input(day, seed, size)?))?;
+    Ok([solution.part1(model.as_ref())?, solution.part2(model.as_ref())?])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generate::*;
+
+    #[test]
+    pub fn test_generate() {
+        // Whether each input is valid for its day is checked next to its
+        // generator, in test_dayN_generate; this covers the plumbing.
+        for (day, size) in [(7, 40), (8, 60), (19, 30), (20, 3), (22, 10), (24, 50)].iter().copied() {
+            for seed in 0..3 {
+                if let Err(e) = solve(day, seed, Some(size)) {
+                    panic!("day {} seed {}: {}", day, seed, e);
+                }
+            }
+            assert_eq!(input(day, 1, Some(size)).unwrap(), input(day, 1, Some(size)).unwrap());
+            assert_ne!(input(day, 1, Some(size)).unwrap(), input(day, 2, Some(size)).unwrap());
+        }
+        assert_eq!(input(1, 0, None).err().unwrap().to_string(), "report_repair has no input generator");
+    }
+}
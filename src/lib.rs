@@ -0,0 +1,47 @@
+// Advent of Code 2020. Each day lives in its own module and implements
+// `solution::Solution`; `runner` ties them together and the remaining
+// modules are the tooling the aoc2020 binary is built from.
+
+#[doc(hidden)]
+pub use anyhow;
+
+pub mod utils;
+pub mod animate;
+pub mod batch;
+pub mod bench;
+pub mod examples;
+pub mod export;
+pub mod generate;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod snapshot;
+pub mod solution;
+pub mod submit;
+pub mod verify;
+pub mod watch;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
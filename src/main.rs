@@ -1,67 +1,483 @@
-#![feature(try_trait)]
-#![feature(map_into_keys_values)]
-#![feature(hash_drain_filter)]
-#![feature(hash_set_entry)]
-#![feature(min_const_generics)]
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-mod utils;
-//mod day1;
-//mod day2;
-//mod day3;
-//mod day4;
-//mod day5;
-//mod day6;
-//mod day7;
-//mod day8;
-//mod day9;
-//mod day10;
-//mod day11;
-//mod day12;
-//mod day13;
-//mod day14;
-//mod day15;
-//mod day16;
-//mod day17;
-//mod day18;
-//mod day19;
-//mod day20;
-//mod day21;
-//mod day22;
-//mod day23;
-//mod day24;
-mod day25;
+use aoc2020::animate::{self, AnimateOptions};
+use aoc2020::batch;
+use aoc2020::bench::{self, BenchOptions};
+use aoc2020::export::{self, ExportOptions};
+use aoc2020::report::{self, Format};
+use aoc2020::runner::{self, RunOptions};
+use aoc2020::submit::Submitter;
+use aoc2020::utils::{self, AOCResult, Fetcher, Input, OptionExt};
+use aoc2020::snapshot::{self, Inputs};
+use aoc2020::solution::Answer;
+use aoc2020::watch::{self, WatchOptions};
+use aoc2020::{aocbail, examples, generate, scaffold, verify};
 
-/**
+/*
  *  Helpful links:
  *
  *  Need to convert raw input to something easier to copy-paste?
  *    https://convert.town/replace-new-lines-with-commas
  */
 
+const USAGE: &str = "usage:
+  aoc2020 run <days> [--part 1|2] [--input <path>|-] [--input-dir <dir>] [--profile <name>] [--timeout <secs>]
+                     [--parallel] [--jobs N] [--format text|json|csv] [--animate [--delay <ms>]]
+  aoc2020 batch <days> [--part 1|2] [--input-dir <dir>] [--profiles <a,b,..>] [--timeout <secs>] [--jobs N]
+                       [--format text|json|csv]
+  aoc2020 bench <days> [run options] [--runs N] [--save <file>] [--baseline <file>] [--threshold <pct>]
+  aoc2020 export <days> [run options] [--image png|ppm] [--cell N] [--palette <spec>] [--out-dir <dir>]
+  aoc2020 verify <days> [--part 1|2] [--input-dir <dir>] [--manifest <file>]
+  aoc2020 snapshot <days> [--update] [--examples] [--input-dir <dir>] [--manifest <file>]
+  aoc2020 watch <day> [--input-dir <dir>] [--timeout <secs>] [--interval <ms>]
+  aoc2020 gen <day> [--seed N] [--size N] [--out <file>] [--solve]
+  aoc2020 new-day <day> [<name>] [--input-dir <dir>]
+  aoc2020 examples <day> <puzzle.html> [--input-dir <dir>] [--force]
+  aoc2020 fetch <days> [--input-dir <dir>] [--base-url <url>]
+  aoc2020 submit <day> --part 1|2 [--answer <answer>] [--input-dir <dir>] [--base-url <url>]
+
+  <days>         a single day (7), a range (1-25), a list (1,3,10-12) or 'all'
+  --input        read a single day's input from a file, or '-' for stdin
+  --input-dir    look up dayN files here instead of $AOC_INPUT_DIR or input/
+  --profile      read real inputs from <input dir>/<name>/dayN instead ($AOC_PROFILE);
+                 examples are still shared
+  --profiles     which profiles batch runs, default every directory of dayN inputs; it
+                 prints their answers side by side and marks parts that fail on some only
+  --timeout      give up on a parse or part that runs longer; the run carries on with
+                 the next day and reports it as timed out
+  --parallel     solve the days on a thread pool and print one summary table
+  --jobs         worker threads for --parallel (implies it, defaults to the CPU count)
+  --format       print one record per day and part as JSON or CSV instead
+  --animate      redraw simulating days (11, 17, 23, 24) in the terminal each step;
+                 space pauses, n steps and q stops
+  --delay        milliseconds between animation frames (default 200)
+  --runs         how many times bench repeats each phase (default 5)
+  --save         write the measured medians to a baseline file
+  --baseline     compare against a saved baseline and flag regressions
+  --threshold    percent slowdown tolerated before flagging (default 10)
+  --image        image format to export (default png)
+  --cell         pixels per side of each exported cell (default 4)
+  --palette      colours for exported glyphs over the defaults, as '#=202020,O=d83c28,bg=ffffff'
+  --out-dir      where exported images go, as dayN-partP.png (default .)
+  --update       record new snapshots instead of failing on differences
+  --examples     only snapshot the test_dayN inputs, not the real ones; which inputs
+                 and parts get snapshots comes from the answer manifest
+  --interval     milliseconds between checks of the watched day's source and inputs
+                 (default 500); each change re-runs its tests and answers
+  --seed         seed for gen, so an input can be made again (default: from the clock)
+  --size         how big a generated input is, in the day's own terms such as bags (7),
+                 instructions (8), messages (19), tiles per side (20), cards per player (22)
+                 or paths (24); default about the size of a real input
+  --out          write the generated input to a file instead of stdout
+  --solve        also solve the generated input, printing the answers to stderr
+  --manifest     expected answers to verify against (default <input dir>/answers)
+  --base-url     puzzle server to talk to (default $AOC_BASE_URL or adventofcode.com/2020)
+  --answer       submit this instead of solving the day's input
+  --force        let examples overwrite existing test_dayN files
+
+Missing dayN inputs are downloaded automatically when $AOC_SESSION holds a session token.
+Built with `--features count-allocs`, run and bench also report allocations per parse and part.";
+
+// Parses the options every command shares. Anything else is offered to
+// `extra` along with the remaining arguments, so commands can add flags.
+fn parse_run_args(
+    mut args: impl Iterator<Item = String>,
+    mut extra: impl FnMut(&str, &mut dyn Iterator<Item = String>) -> AOCResult<bool>,
+) -> AOCResult<(Vec<u8>, RunOptions)> {
+    let mut days = None;
+    let mut options = RunOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => options.part = Some(parse_part(&mut args)?),
+            "--input" | "-i" => {
+                options.input = Some(match args.next().ok_or_none()?.as_str() {
+                    "-" => Input::Stdin,
+                    path => Input::Path(path.into()),
+                });
+            }
+            "--input-dir" => utils::set_input_dir(args.next().ok_or_none()?),
+            "--profile" => utils::set_profile(args.next().ok_or_none()?),
+            "--timeout" => {
+                let seconds = args.next().ok_or_none()?.parse::<f64>()?;
+                if seconds.is_nan() || seconds <= 0.0 {
+                    aocbail!("--timeout must be a positive number of seconds, got {}", seconds);
+                }
+                options.timeout = Some(Duration::from_secs_f64(seconds));
+            }
+            flag if flag.starts_with('-') && extra(flag, &mut args)? => {}
+            _ if days.is_none() => days = Some(runner::parse_days(&arg)?),
+            _ => aocbail!("Unexpected argument '{}'", arg),
+        }
+    }
+
+    let days = match days {
+        Some(days) => days,
+        None => aocbail!("{}", USAGE),
+    };
+    if options.input.is_some() && days.len() > 1 {
+        aocbail!("--input can only be used when running a single day");
+    }
+    Ok((days, options))
+}
+
+fn parse_part(args: &mut dyn Iterator<Item = String>) -> AOCResult<u8> {
+    let part = args.next().ok_or_none()?.parse::<u8>()?;
+    if part != 1 && part != 2 {
+        aocbail!("--part must be 1 or 2, got {}", part);
+    }
+    Ok(part)
+}
+
+// Parses the days and server options of fetch and submit, offering other
+// flags to `extra` like parse_run_args. Neither solves with run options,
+// so unlike there any flag left over is an error.
+fn parse_server_args(
+    mut args: impl Iterator<Item = String>,
+    mut extra: impl FnMut(&str, &mut dyn Iterator<Item = String>) -> AOCResult<bool>,
+) -> AOCResult<(Vec<u8>, Option<String>)> {
+    let (mut days, mut base_url) = (None, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input-dir" => utils::set_input_dir(args.next().ok_or_none()?),
+            "--base-url" => base_url = Some(args.next().ok_or_none()?),
+            flag if flag.starts_with('-') => {
+                if !extra(flag, &mut args)? {
+                    aocbail!("Unknown option '{}'", flag);
+                }
+            }
+            _ if days.is_none() => days = Some(runner::parse_days(&arg)?),
+            _ => aocbail!("Unexpected argument '{}'", arg),
+        }
+    }
+    match days {
+        Some(days) => Ok((days, base_url)),
+        None => aocbail!("{}", USAGE),
+    }
+}
+
+fn run(mut args: impl Iterator<Item = String>) -> AOCResult<()> {
+    match args.next().as_deref() {
+        Some("run") => {
+            let (mut jobs, mut format) = (None, Format::Text);
+            let (mut animate, mut animation) = (false, AnimateOptions::default());
+            let (days, options) = parse_run_args(args, |flag, args| {
+                match flag {
+                    "--parallel" => jobs = jobs.or_else(|| Some(utils::default_jobs())),
+                    "--jobs" | "-j" => jobs = Some(args.next().ok_or_none()?.parse::<usize>()?),
+                    "--format" => format = args.next().ok_or_none()?.parse::<Format>()?,
+                    "--animate" => animate = true,
+                    "--delay" => animation.delay = Duration::from_millis(args.next().ok_or_none()?.parse::<u64>()?),
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if animate {
+                if jobs.is_some() || format != Format::Text {
+                    aocbail!("--animate can't be combined with --parallel, --jobs or --format");
+                }
+                let mut failed = 0;
+                for day in days {
+                    failed += animate::run(day, &options, &animation)?.failed() as usize;
+                }
+                if failed > 0 {
+                    aocbail!("{} day(s) failed", failed);
+                }
+                return Ok(());
+            }
+            if jobs.is_some() || format != Format::Text {
+                let start = Instant::now();
+                let reports = runner::run_parallel(&days, &options, jobs.unwrap_or(1))?;
+                let records = report::records(&reports, &options.parts());
+                match format {
+                    Format::Text => print!("{}", report::text(&reports, start.elapsed())),
+                    Format::Json => print!("{}", report::json(&records)),
+                    Format::Csv => print!("{}", report::csv(&records)),
+                }
+                let failed = reports.iter().filter(|r| r.failed()).count();
+                if failed > 0 {
+                    aocbail!("{} day(s) failed", failed);
+                }
+                return Ok(());
+            }
+            let mut failed = 0;
+            for day in days {
+                println!("--- day {} ---", day);
+                failed += runner::run_day(day, &options)?.failed() as usize;
+            }
+            if failed > 0 {
+                aocbail!("{} day(s) failed", failed);
+            }
+            Ok(())
+        }
+        Some("batch") => {
+            let (mut profiles, mut jobs, mut format) = (None, utils::default_jobs(), Format::Text);
+            let (days, options) = parse_run_args(args, |flag, args| {
+                match flag {
+                    "--profiles" => {
+                        profiles = Some(args.next().ok_or_none()?.split(',').map(str::to_owned).collect::<Vec<_>>())
+                    }
+                    "--jobs" | "-j" => jobs = args.next().ok_or_none()?.parse::<usize>()?,
+                    "--format" => format = args.next().ok_or_none()?.parse::<Format>()?,
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if options.input.is_some() || utils::profile().is_some() {
+                aocbail!("batch reads every profile's own inputs, --input and --profile are not supported");
+            }
+            let dir = utils::input_dir();
+            let profiles = match profiles {
+                Some(profiles) => profiles,
+                None => utils::profiles(&dir)?,
+            };
+            let batch = batch::run(&dir, &days, &profiles, &options, jobs)?;
+            match format {
+                Format::Text => print!("{}", batch.table()),
+                Format::Json => print!("{}", report::json(&batch.records())),
+                Format::Csv => print!("{}", report::csv(&batch.records())),
+            }
+            let failed = batch.failed();
+            if failed > 0 {
+                aocbail!("{} part(s) failed on at least one profile", failed);
+            }
+            Ok(())
+        }
+        Some("bench") => {
+            let mut bench = BenchOptions::default();
+            let (days, options) = parse_run_args(args, |flag, args| {
+                match flag {
+                    "--runs" => bench.runs = args.next().ok_or_none()?.parse::<usize>()?,
+                    "--save" => bench.save = Some(args.next().ok_or_none()?.into()),
+                    "--baseline" => bench.baseline = Some(args.next().ok_or_none()?.into()),
+                    "--threshold" => {
+                        bench.threshold = args.next().ok_or_none()?.parse::<f64>()? / 100.0
+                    }
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if bench.runs == 0 {
+                aocbail!("--runs must be at least 1");
+            }
+            bench::run(&days, &options, &bench)
+        }
+        Some("export") => {
+            let mut export = ExportOptions::default();
+            let (days, options) = parse_run_args(args, |flag, args| {
+                match flag {
+                    "--image" => export.format = args.next().ok_or_none()?.parse()?,
+                    "--cell" => export.cell = args.next().ok_or_none()?.parse::<usize>()?,
+                    "--palette" => export.palette = args.next().ok_or_none()?.parse()?,
+                    "--out-dir" => export.dir = args.next().ok_or_none()?.into(),
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if export.cell == 0 {
+                aocbail!("--cell must be at least 1");
+            }
+            for day in days {
+                for path in export::run(day, &options, &export)? {
+                    println!("wrote {}", path.display());
+                }
+            }
+            Ok(())
+        }
+        Some("verify") => {
+            let mut manifest = None;
+            let (days, options) = parse_run_args(args, |flag, args| {
+                if flag != "--manifest" {
+                    return Ok(false);
+                }
+                manifest = Some(PathBuf::from(args.next().ok_or_none()?));
+                Ok(true)
+            })?;
+            if options.input.is_some() {
+                aocbail!("verify reads its inputs from the manifest, --input is not supported");
+            }
+            verify::run(&days, &options, &manifest.unwrap_or_else(verify::manifest_path))
+        }
+        Some("snapshot") => {
+            let (mut update, mut which, mut manifest) = (false, Inputs::All, None);
+            let (days, options) = parse_run_args(args, |flag, args| {
+                match flag {
+                    "--update" => update = true,
+                    "--examples" => which = Inputs::Examples,
+                    "--manifest" => manifest = Some(PathBuf::from(args.next().ok_or_none()?)),
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if options.input.is_some() || options.part.is_some() {
+                aocbail!("snapshot covers every input and part of a day, --input and --part are not supported");
+            }
+            let manifest = manifest.unwrap_or_else(verify::manifest_path);
+            let failed = snapshot::run(&days, which, update, &manifest, utils::default_jobs())?;
+            if failed > 0 {
+                aocbail!("{} snapshot(s) did not match", failed);
+            }
+            Ok(())
+        }
+        Some("watch") => {
+            let mut watch = WatchOptions::default();
+            let (days, options) = parse_run_args(args, |flag, args| {
+                if flag != "--interval" {
+                    return Ok(false);
+                }
+                watch.interval = Duration::from_millis(args.next().ok_or_none()?.parse::<u64>()?);
+                Ok(true)
+            })?;
+            let day = match days.as_slice() {
+                [day] => *day,
+                _ => aocbail!("watch follows a single day"),
+            };
+            if options.input.is_some() || options.part.is_some() {
+                aocbail!("watch re-runs every part on the day's own inputs, --input and --part are not supported");
+            }
+            watch.timeout = options.timeout;
+            watch::run(day, &watch)
+        }
+        Some("fetch") => {
+            let (days, base_url) = parse_server_args(args, |_, _| Ok(false))?;
+            let mut fetcher = match Fetcher::from_env() {
+                Some(fetcher) => fetcher,
+                None => aocbail!("Set ${} to your session token to fetch inputs", utils::SESSION_ENV),
+            };
+            if let Some(base_url) = base_url {
+                fetcher.base_url = base_url;
+            }
+            for day in days {
+                let cached = fetcher.path(day).exists();
+                let path = fetcher.fetch(day)?;
+                println!("day {}: {} ({})", day, path.display(), if cached { "cached" } else { "downloaded" });
+            }
+            Ok(())
+        }
+        Some("submit") => {
+            let (mut part, mut answer) = (None, None);
+            let (days, base_url) = parse_server_args(args, |flag, args| {
+                match flag {
+                    "--part" | "-p" => part = Some(parse_part(args)?),
+                    "--answer" => answer = Some(args.next().ok_or_none()?),
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            let (day, part) = match (days.as_slice(), part) {
+                ([day], Some(part)) => (*day, part),
+                _ => aocbail!("submit needs a single day and --part"),
+            };
+            let mut submitter = match Submitter::from_env() {
+                Some(submitter) => submitter,
+                None => aocbail!("Set ${} to your session token to submit answers", utils::SESSION_ENV),
+            };
+            if let Some(base_url) = base_url {
+                submitter.base_url = base_url;
+            }
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    let solution = runner::solution(day)?;
+                    let model = solution.parse(&RunOptions::default().input(solution))?;
+                    match solution.part(part, model.as_ref())? {
+                        Answer::None => aocbail!("{} has no part {}", solution.name(), part),
+                        answer => answer.to_string(),
+                    }
+                }
+            };
+            println!("day {} part {}: submitting {}", day, part, answer);
+            println!("{}", submitter.submit(day, part, &answer)?);
+            Ok(())
+        }
+        Some("examples") => {
+            let mut positional = Vec::new();
+            let mut force = false;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input-dir" => utils::set_input_dir(args.next().ok_or_none()?),
+                    "--force" => force = true,
+                    _ => positional.push(arg),
+                }
+            }
+            let (day, page) = match positional.as_slice() {
+                [day, page] => (day.parse::<u8>()?, std::fs::read_to_string(page)?),
+                _ => aocbail!("{}", USAGE),
+            };
+            let found = examples::extract_examples(&page);
+            if found.is_empty() {
+                aocbail!("No <pre><code> examples found");
+            }
+            for path in examples::write_examples(&utils::input_dir(), day, &found, force)? {
+                println!("wrote {}", path.display());
+            }
+            println!("candidate answers: {}", examples::candidate_answers(&page).join(", "));
+            Ok(())
+        }
+        Some("gen") => {
+            let (mut day, mut seed, mut size, mut out, mut solve) = (None, None, None, None, false);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--seed" => seed = Some(args.next().ok_or_none()?.parse::<u64>()?),
+                    "--size" => size = Some(args.next().ok_or_none()?.parse::<usize>()?),
+                    "--out" => out = Some(PathBuf::from(args.next().ok_or_none()?)),
+                    "--solve" => solve = true,
+                    _ if day.is_none() => day = Some(arg.parse::<u8>()?),
+                    _ => aocbail!("Unexpected argument '{}'", arg),
+                }
+            }
+            let day = match day {
+                Some(day) => day,
+                None => aocbail!("{}", USAGE),
+            };
+            let seed = match seed {
+                Some(seed) => seed,
+                None => {
+                    let seed = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_nanos() as u64;
+                    eprintln!("seed {}", seed);
+                    seed
+                }
+            };
+            let input = generate::input(day, seed, size)?;
+            match &out {
+                Some(path) => {
+                    std::fs::write(path, &input)?;
+                    println!("wrote {}", path.display());
+                }
+                None => print!("{}", input),
+            }
+            if solve {
+                let [part1, part2] = generate::solve(day, seed, size)?;
+                eprintln!("part 1: {}\npart 2: {}", part1, part2);
+            }
+            Ok(())
+        }
+        Some("new-day") => {
+            let mut positional = Vec::new();
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input-dir" => utils::set_input_dir(args.next().ok_or_none()?),
+                    _ => positional.push(arg),
+                }
+            }
+            let (day, name) = match positional.as_slice() {
+                [day] => (day.parse::<u8>()?, format!("day{}", day)),
+                [day, name] => (day.parse::<u8>()?, name.clone()),
+                _ => aocbail!("{}", USAGE),
+            };
+            let src = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src");
+            for path in scaffold::new_day(&src, &utils::input_dir(), day, &name)? {
+                println!("wrote {}", path.display());
+            }
+            Ok(())
+        }
+        _ => aocbail!("{}", USAGE),
+    }
+}
+
 fn main() {
-    //day1::day1();
-    //day2::day2();
-    //day3::day3();
-    //day4::day4();
-    //day5::day5();
-    //day6::day6();
-    //day7::day7();
-    //day8::day8();
-    //day9::day9();
-    //day10::day10();
-    //day11::day11();
-    //day12::day12();
-    //day13::day13();
-    //day14::day14();
-    //day15::day15();
-    //day16::day16();
-    //day17::day17();
-    //day18::day18();
-    //day19::day19();
-    //day20::day20();
-    //day21::day21();
-    //day22::day22();
-    //day23::day23();
-    //day24::day24();
-    day25::day25();
-}
\ No newline at end of file
+    if let Err(e) = run(std::env::args().skip(1)) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
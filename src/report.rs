@@ -0,0 +1,312 @@
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::runner::DayReport;
+use crate::solution::Answer;
+use crate::utils::{format_bytes, format_duration, AOCError, AOCResult, Allocs};
+use crate::aocbail;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = AOCError;
+
+    fn from_str(s: &str) -> AOCResult<Format> {
+        Ok(match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            _ => aocbail!("Unknown format '{}', expected text, json or csv", s),
+        })
+    }
+}
+
+// One day/part, flattened out of a DayReport. A day that failed to parse
+// still gets a record per part, carrying the parse error. `status` is "ok",
+// "n/a" for a part the day doesn't have, or how the part failed: "error",
+// "panicked", "timed out" or "not run".
+pub struct Record {
+    pub day: u8,
+    pub name: &'static str,
+    pub part: u8,
+    pub status: &'static str,
+    pub kind: &'static str,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub input: String,
+    pub parse_elapsed: Duration,
+    pub elapsed: Duration,
+    // Only counted with the count-allocs feature.
+    pub parse_allocs: Option<Allocs>,
+    pub allocs: Option<Allocs>,
+}
+
+pub fn records(reports: &[DayReport], parts: &[u8]) -> Vec<Record> {
+    let mut records = Vec::new();
+    for report in reports {
+        let record = |part: u8| Record {
+            day: report.day,
+            name: report.name,
+            part,
+            status: "ok",
+            kind: "error",
+            answer: None,
+            error: None,
+            input: report.input.to_string(),
+            parse_elapsed: report.parse_elapsed,
+            elapsed: Duration::default(),
+            parse_allocs: report.parse_allocs,
+            allocs: None,
+        };
+        match &report.parsed {
+            Ok(()) => records.extend(report.parts.iter().map(|part| match &part.answer {
+                Ok(Answer::None) => Record {
+                    status: "n/a",
+                    kind: Answer::None.kind(),
+                    elapsed: part.elapsed,
+                    allocs: part.allocs,
+                    ..record(part.part)
+                },
+                Ok(answer) => Record {
+                    kind: answer.kind(),
+                    answer: Some(answer.to_string()),
+                    elapsed: part.elapsed,
+                    allocs: part.allocs,
+                    ..record(part.part)
+                },
+                Err(failure) => Record {
+                    status: failure.status(),
+                    error: Some(failure.message()),
+                    elapsed: part.elapsed,
+                    allocs: part.allocs,
+                    ..record(part.part)
+                },
+            })),
+            Err(failure) => records.extend(parts.iter().map(|part| Record {
+                status: failure.status(),
+                error: Some(format!("parse {}", failure)),
+                ..record(*part)
+            })),
+        }
+    }
+    records
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_option(s: &Option<String>) -> String {
+    s.as_deref().map_or_else(|| "null".to_owned(), json_string)
+}
+
+// count, bytes and peak, or nothing when allocations weren't counted.
+fn alloc_fields(allocs: Option<Allocs>) -> [Option<u64>; 3] {
+    match allocs {
+        Some(allocs) => [Some(allocs.count), Some(allocs.bytes), Some(allocs.peak)],
+        None => [None; 3],
+    }
+}
+
+const ALLOC_FIELDS: [&str; 6] = ["parse_allocs", "parse_alloc_bytes", "parse_peak_bytes", "allocs", "alloc_bytes", "peak_bytes"];
+
+fn allocs(r: &Record) -> impl Iterator<Item = (&'static str, Option<u64>)> {
+    let fields = alloc_fields(r.parse_allocs).iter().chain(alloc_fields(r.allocs).iter()).copied().collect::<Vec<_>>();
+    ALLOC_FIELDS.iter().copied().zip(fields)
+}
+
+// Answers are always strings so big integers survive JSON parsers that
+// read numbers as doubles; `type` says how to interpret them.
+pub fn json(records: &[Record]) -> String {
+    let lines = records
+        .iter()
+        .map(|r| {
+            let allocs = allocs(r)
+                .map(|(name, value)| format!(", \"{}\": {}", name, value.map_or_else(|| "null".to_owned(), |v| v.to_string())))
+                .collect::<String>();
+            format!(
+                "  {{\"day\": {}, \"name\": {}, \"part\": {}, \"status\": {}, \"type\": {}, \"answer\": {}, \"error\": {}, \"input\": {}, \"parse_ns\": {}, \"elapsed_ns\": {}{}}}",
+                r.day,
+                json_string(r.name),
+                r.part,
+                json_string(r.status),
+                json_string(r.kind),
+                json_option(&r.answer),
+                json_option(&r.error),
+                json_string(&r.input),
+                r.parse_elapsed.as_nanos(),
+                r.elapsed.as_nanos(),
+                allocs
+            )
+        })
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        return "[]\n".to_owned();
+    }
+    format!("[\n{}\n]\n", lines.join(",\n"))
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+pub fn csv(records: &[Record]) -> String {
+    let mut out = format!("day,name,part,status,type,answer,error,input,parse_ns,elapsed_ns,{}\n", ALLOC_FIELDS.join(","));
+    for r in records {
+        let allocs = allocs(r).map(|(_, value)| value.map_or_else(String::new, |v| v.to_string())).collect::<Vec<_>>();
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            r.day,
+            csv_field(r.name),
+            r.part,
+            r.status,
+            r.kind,
+            csv_field(r.answer.as_deref().unwrap_or("")),
+            csv_field(r.error.as_deref().unwrap_or("")),
+            csv_field(&r.input),
+            r.parse_elapsed.as_nanos(),
+            r.elapsed.as_nanos(),
+            allocs.join(",")
+        );
+    }
+    out
+}
+
+// Allocation columns for text tables, blank for phases without counts.
+pub fn alloc_columns(allocs: Option<Allocs>) -> String {
+    match allocs {
+        Some(a) => format!(" {:>9} {:>10} {:>10}", a.count, format_bytes(a.bytes), format_bytes(a.peak)),
+        None => format!(" {:>9} {:>10} {:>10}", "", "", ""),
+    }
+}
+
+pub fn alloc_header() -> String {
+    format!(" {:>9} {:>10} {:>10}", "allocs", "allocated", "peak")
+}
+
+pub fn text(reports: &[DayReport], wall_time: Duration) -> String {
+    let counted = reports.iter().any(|r| r.parse_allocs.is_some());
+    let columns = |allocs| if counted { alloc_columns(allocs) } else { String::new() };
+    let mut out = String::new();
+    let header = if counted { alloc_header() } else { String::new() };
+    let _ = writeln!(out, "{:>3}  {:<24} {:<6} {:>10}{}  answer", "day", "name", "phase", "time", header);
+    for report in reports {
+        let parsed = match &report.parsed {
+            Ok(()) => String::new(),
+            Err(failure) => failure.to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "{:>3}  {:<24} {:<6} {:>10}{}  {}",
+            report.day,
+            report.name,
+            "parse",
+            format_duration(report.parse_elapsed),
+            columns(report.parse_allocs),
+            parsed
+        );
+        for part in &report.parts {
+            let answer = match &part.answer {
+                Ok(answer) => answer.to_string(),
+                Err(failure) => failure.to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "{:>3}  {:<24} {:<6} {:>10}{}  {}",
+                report.day,
+                report.name,
+                format!("part{}", part.part),
+                format_duration(part.elapsed),
+                columns(part.allocs),
+                answer
+            );
+        }
+    }
+
+    let work = reports
+        .iter()
+        .map(|r| r.parse_elapsed + r.parts.iter().map(|p| p.elapsed).sum::<Duration>())
+        .sum::<Duration>();
+    let _ = writeln!(
+        out,
+        "{} day(s), {} failed, {} wall time, {} of work",
+        reports.len(),
+        reports.iter().filter(|r| r.failed()).count(),
+        format_duration(wall_time),
+        format_duration(work)
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::*;
+    use crate::runner::{self, RunOptions};
+    use crate::utils::Input;
+
+    #[test]
+    pub fn test_formats() {
+        let options = RunOptions {
+            part: None,
+            input: Some(Input::from("test_day21")),
+            ..RunOptions::default()
+        };
+        let reports = runner::run_parallel(&[21, 5], &options, 1).unwrap();
+        let records = records(&reports, &options.parts());
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].kind, "int");
+        assert_eq!(records[1].answer.as_deref(), Some("mxmxvkd,sqjhc,fvjkl"));
+
+        let json = json(&records);
+        assert!(json.starts_with("[\n  {\"day\": 21, \"name\": \"allergen_assessment\", \"part\": 1, \"status\": \"ok\", \"type\": \"int\", \"answer\": \"5\", \"error\": null,"));
+        assert_eq!(json.lines().count(), 6);
+        assert_eq!(json_string("a\"b\\\n"), "\"a\\\"b\\\\\\n\"");
+
+        let csv = csv(&records);
+        assert!(csv.lines().next().unwrap().ends_with(",elapsed_ns,parse_allocs,parse_alloc_bytes,parse_peak_bytes,allocs,alloc_bytes,peak_bytes"));
+        assert!(csv.lines().nth(2).unwrap().starts_with("21,allergen_assessment,2,ok,text,\"mxmxvkd,sqjhc,fvjkl\",,"));
+        assert!(csv.lines().nth(3).unwrap().starts_with("5,binary_boarding,1,error,error,,"));
+
+        // Day 25 has no part 2, which is reported as such rather than failing.
+        let options = RunOptions {
+            part: None,
+            input: Some(Input::Text("5764801\n17807724\n".to_owned())),
+            ..RunOptions::default()
+        };
+        let reports = runner::run_parallel(&[25], &options, 1).unwrap();
+        assert!(!reports[0].failed());
+        let missing = super::records(&reports, &options.parts());
+        assert_eq!((missing[1].status, missing[1].kind, missing[1].answer.as_deref()), ("n/a", "none", None));
+        assert!(super::json(&missing).contains("\"part\": 2, \"status\": \"n/a\", \"type\": \"none\", \"answer\": null,"));
+        assert!(super::csv(&missing).lines().nth(2).unwrap().starts_with("25,combo_breaker,2,n/a,none,,,"));
+        let text = text(&reports, Duration::default());
+        assert!(text.lines().nth(3).unwrap().ends_with("  n/a"));
+        assert!(text.contains("1 day(s), 0 failed,"));
+    }
+}
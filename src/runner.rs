@@ -0,0 +1,392 @@
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::solution::{Answer, Day, Registered};
+use crate::utils::{self, format_duration, AOCResult, Allocs, Input};
+use crate::aocbail;
+
+pub const DAYS: RangeInclusive<u8> = 1..=25;
+
+// Ordered by day. `new-day` inserts new entries here, so keep one per line.
+static REGISTRY: &[&dyn Day] = &[
+    &Registered::<crate::day1::Day1>(PhantomData),
+    &Registered::<crate::day2::Day2>(PhantomData),
+    &Registered::<crate::day3::Day3>(PhantomData),
+    &Registered::<crate::day4::Day4>(PhantomData),
+    &Registered::<crate::day5::Day5>(PhantomData),
+    &Registered::<crate::day6::Day6>(PhantomData),
+    &Registered::<crate::day7::Day7>(PhantomData),
+    &Registered::<crate::day8::Day8>(PhantomData),
+    &Registered::<crate::day9::Day9>(PhantomData),
+    &Registered::<crate::day10::Day10>(PhantomData),
+    &Registered::<crate::day11::Day11>(PhantomData),
+    &Registered::<crate::day12::Day12>(PhantomData),
+    &Registered::<crate::day13::Day13>(PhantomData),
+    &Registered::<crate::day14::Day14>(PhantomData),
+    &Registered::<crate::day15::Day15>(PhantomData),
+    &Registered::<crate::day16::Day16>(PhantomData),
+    &Registered::<crate::day17::Day17>(PhantomData),
+    &Registered::<crate::day18::Day18>(PhantomData),
+    &Registered::<crate::day19::Day19>(PhantomData),
+    &Registered::<crate::day20::Day20>(PhantomData),
+    &Registered::<crate::day21::Day21>(PhantomData),
+    &Registered::<crate::day22::Day22>(PhantomData),
+    &Registered::<crate::day23::Day23>(PhantomData),
+    &Registered::<crate::day24::Day24>(PhantomData),
+    &Registered::<crate::day25::Day25>(PhantomData),
+];
+
+pub fn registry() -> &'static [&'static dyn Day] {
+    REGISTRY
+}
+
+pub fn solution(day: u8) -> AOCResult<&'static dyn Day> {
+    match registry().iter().find(|solution| solution.day() == day) {
+        Some(solution) => Ok(*solution),
+        None => aocbail!("No solution registered for day {}", day),
+    }
+}
+
+// Options shared by every run. `part` narrows a run down to a single
+// part, `input` swaps the default `input/dayN` file for another source and
+// `timeout` gives up on a parse or part that runs longer.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    pub part: Option<u8>,
+    pub input: Option<Input>,
+    pub timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    pub fn part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    pub fn parts(&self) -> Vec<u8> {
+        (1..=2).filter(|p| self.part(*p)).collect()
+    }
+
+    pub fn input(&self, solution: &dyn Day) -> Input {
+        self.input
+            .clone()
+            .unwrap_or_else(|| solution.default_input())
+    }
+}
+
+// Accepts "7", "1-25", "1,3,10-12" or "all".
+pub fn parse_days(spec: &str) -> AOCResult<Vec<u8>> {
+    if spec == "all" {
+        return Ok(DAYS.collect());
+    }
+
+    let mut days = Vec::new();
+    for chunk in spec.split(',') {
+        let (start, end) = match chunk.find('-') {
+            Some(i) => (chunk[..i].parse::<u8>()?, chunk[i + 1..].parse::<u8>()?),
+            None => {
+                let day = chunk.parse::<u8>()?;
+                (day, day)
+            }
+        };
+        if !DAYS.contains(&start) || !DAYS.contains(&end) || start > end {
+            aocbail!("Invalid day range '{}', expected days within 1-25", chunk);
+        }
+        days.extend(start..=end);
+    }
+    Ok(days)
+}
+
+// Solves `day` and prints its answers, and how any phase failed on stderr.
+// Failures are left in the report for the caller to count, so one day
+// going wrong doesn't stop the days after it.
+pub fn run_day(day: u8, options: &RunOptions) -> AOCResult<DayReport> {
+    let report = solve(solution(day)?, options);
+    if let Err(failure) = &report.parsed {
+        eprintln!("{} parse: {}", report.name, failure);
+    }
+    for part in &report.parts {
+        match &part.answer {
+            Ok(answer) => println!("{} part {}: {}", report.name, part.part, answer),
+            Err(failure) => eprintln!("{} part {}: {}", report.name, part.part, failure),
+        }
+    }
+    // Only there with the count-allocs feature.
+    let phases = std::iter::once(("parse".to_owned(), report.parse_allocs))
+        .chain(report.parts.iter().map(|part| (format!("part{}", part.part), part.allocs)));
+    for (phase, allocs) in phases {
+        if let Some(allocs) = allocs {
+            println!("{} {} allocations: {}", report.name, phase, allocs);
+        }
+    }
+    Ok(report)
+}
+
+// Why a parse or part produced no answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+    Error(String),
+    Panicked(String),
+    TimedOut(Duration),
+    // An earlier phase timed out and is still holding the model.
+    NotRun,
+}
+
+impl Failure {
+    pub fn status(&self) -> &'static str {
+        match self {
+            Failure::Error(_) => "error",
+            Failure::Panicked(_) => "panicked",
+            Failure::TimedOut(_) => "timed out",
+            Failure::NotRun => "not run",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Failure::Error(e) | Failure::Panicked(e) => e.clone(),
+            Failure::TimedOut(after) => format!("timed out after {}", format_duration(*after)),
+            Failure::NotRun => "not run, an earlier phase timed out".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Error(_) | Failure::Panicked(_) => write!(f, "{}: {}", self.status(), self.message()),
+            _ => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+pub struct PartReport {
+    pub part: u8,
+    pub elapsed: Duration,
+    // What the part allocated, when allocations are being counted.
+    pub allocs: Option<Allocs>,
+    pub answer: Result<Answer, Failure>,
+}
+
+// Everything one day produced. Failures are kept as values so a failing
+// day can sit in a report next to the ones that worked.
+pub struct DayReport {
+    pub day: u8,
+    pub name: &'static str,
+    pub input: Input,
+    pub parse_elapsed: Duration,
+    pub parse_allocs: Option<Allocs>,
+    pub parsed: Result<(), Failure>,
+    pub parts: Vec<PartReport>,
+}
+
+impl DayReport {
+    // Stands in for a day whose worker died before it could report.
+    pub fn panicked(solution: &dyn Day, input: Input) -> DayReport {
+        DayReport {
+            day: solution.day(),
+            name: solution.name(),
+            input,
+            parse_elapsed: Duration::default(),
+            parse_allocs: None,
+            parsed: Err(Failure::Panicked("worker panicked".to_owned())),
+            parts: Vec::new(),
+        }
+    }
+
+    // Parts the day doesn't have answer Answer::None, which isn't a failure.
+    pub fn failed(&self) -> bool {
+        self.parsed.is_err() || self.parts.iter().any(|p| p.answer.is_err())
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_owned(),
+        },
+    }
+}
+
+// How long a phase took, what it allocated (if counted) and how it ended.
+type Phase<T> = (Duration, Option<Allocs>, Result<T, Failure>);
+
+// Runs `f`, turning errors and panics into failures.
+fn isolate<T>(f: impl FnOnce() -> AOCResult<T>) -> Phase<T> {
+    let start = Instant::now();
+    let (allocs, result) = utils::measure(|| match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(Failure::Error(e.to_string())),
+        Err(payload) => Err(Failure::Panicked(panic_message(payload))),
+    });
+    (start.elapsed(), Some(allocs).filter(|_| utils::counting()), result)
+}
+
+// Parses and solves on a worker thread, so a panic only takes out the
+// phase it happened in. A phase that outlives `options.timeout` is
+// abandoned, still running, and the parts after it are not run.
+pub fn solve(solution: &'static dyn Day, options: &RunOptions) -> DayReport {
+    let input = options.input(solution);
+    let mut report = DayReport {
+        day: solution.day(),
+        name: solution.name(),
+        input: input.clone(),
+        parse_elapsed: Duration::default(),
+        parse_allocs: None,
+        parsed: Ok(()),
+        parts: Vec::new(),
+    };
+
+    let parts = options.parts();
+    let (sender, receiver) = mpsc::channel();
+    let worker_parts = parts.clone();
+    thread::spawn(move || {
+        let (elapsed, allocs, model) = isolate(|| solution.parse(&input));
+        let model = match model {
+            Ok(model) => {
+                let _ = sender.send((elapsed, allocs, Ok(None)));
+                model
+            }
+            Err(failure) => {
+                let _ = sender.send((elapsed, allocs, Err(failure)));
+                return;
+            }
+        };
+        for part in worker_parts {
+            let (elapsed, allocs, answer) = isolate(|| solution.part(part, model.as_ref()));
+            if sender.send((elapsed, allocs, answer.map(Some))).is_err() {
+                return;
+            }
+        }
+    });
+
+    let exited = || (Duration::default(), None, Err(Failure::Panicked("worker exited".to_owned())));
+    let receive = || match options.timeout {
+        Some(timeout) => receiver.recv_timeout(timeout).unwrap_or_else(|e| match e {
+            RecvTimeoutError::Timeout => (timeout, None, Err(Failure::TimedOut(timeout))),
+            RecvTimeoutError::Disconnected => exited(),
+        }),
+        None => receiver.recv().unwrap_or_else(|_| exited()),
+    };
+
+    let (elapsed, allocs, parsed) = receive();
+    report.parse_elapsed = elapsed;
+    report.parse_allocs = allocs;
+    if let Err(failure) = parsed {
+        report.parsed = Err(failure);
+        return report;
+    }
+    let mut stuck = false;
+    for part in parts {
+        let (elapsed, allocs, answer) = if stuck { (Duration::default(), None, Err(Failure::NotRun)) } else { receive() };
+        stuck = stuck || matches!(answer, Err(Failure::TimedOut(_)));
+        report.parts.push(PartReport {
+            part,
+            elapsed,
+            allocs,
+            answer: answer.map(|answer| answer.expect("the worker sends answers for parts")),
+        });
+    }
+    report
+}
+
+// Solves every day on a pool of `jobs` threads. Reports come back in day
+// order; a day whose worker panicked is reported as a parse failure.
+pub fn run_parallel(days: &[u8], options: &RunOptions, jobs: usize) -> AOCResult<Vec<DayReport>> {
+    let solutions = days.iter().map(|day| solution(*day)).collect::<AOCResult<Vec<_>>>()?;
+    let reports = utils::map_parallel(&solutions, jobs, |solution| solve(*solution, options));
+    Ok(solutions
+        .iter()
+        .zip(reports)
+        .map(|(solution, report)| report.unwrap_or_else(|| DayReport::panicked(*solution, options.input(*solution))))
+        .collect())
+}
+
+#[test]
+fn test_run_parallel() {
+    let options = RunOptions {
+        part: None,
+        input: Some(Input::from("test_day6")),
+        ..RunOptions::default()
+    };
+    let reports = run_parallel(&[6, 2], &options, 2).unwrap();
+    assert_eq!(reports.iter().map(|r| r.day).collect::<Vec<_>>(), vec![6, 2]);
+    assert_eq!(reports[0].parts[1].answer.as_ref().unwrap().to_string(), "6");
+    // test_day6 isn't a password list, but day 2 failing mustn't affect day 6.
+    assert!(reports[1].failed());
+    assert!(!reports[0].failed());
+}
+
+#[test]
+fn test_run_day() {
+    // A day that can't parse is reported, not raised, so the days after it
+    // still run.
+    let options = RunOptions {
+        input: Some(Input::from("test_day6")),
+        ..RunOptions::default()
+    };
+    let report = run_day(2, &options).unwrap();
+    assert!(report.failed() && report.parts.is_empty());
+    assert!(!run_day(6, &options).unwrap().failed());
+    assert!(run_day(26, &options).is_err());
+}
+
+#[test]
+fn test_isolation() {
+    // Part 1 panics and part 2 takes far longer than the test's timeout.
+    struct Unruly;
+
+    impl crate::solution::Solution for Unruly {
+        type Model = ();
+        type Part1 = u32;
+        type Part2 = u32;
+
+        const DAY: u8 = 0;
+        const NAME: &'static str = "unruly";
+
+        fn parse(_: &Input) -> AOCResult<()> {
+            Ok(())
+        }
+
+        fn part1(_: &()) -> AOCResult<u32> {
+            panic!("boom")
+        }
+
+        fn part2(_: &()) -> AOCResult<u32> {
+            thread::sleep(Duration::from_secs(5));
+            Ok(2)
+        }
+    }
+
+    static UNRULY: Registered<Unruly> = Registered(PhantomData);
+
+    let options = RunOptions {
+        timeout: Some(Duration::from_millis(200)),
+        ..RunOptions::default()
+    };
+    let report = solve(&UNRULY, &options);
+    assert_eq!(report.parsed, Ok(()));
+    assert_eq!(report.parts[0].answer, Err(Failure::Panicked("boom".to_owned())));
+    assert_eq!(report.parts[1].answer, Err(Failure::TimedOut(Duration::from_millis(200))));
+    assert_eq!(report.parts[1].answer.as_ref().unwrap_err().to_string(), "timed out after 200.00ms");
+
+    let report = solve(&UNRULY, &RunOptions { part: Some(1), ..options });
+    assert_eq!(report.parts.len(), 1);
+    assert!(report.failed());
+}
+
+#[test]
+fn test_registry() {
+    for (i, solution) in registry().iter().enumerate() {
+        assert_eq!(solution.day() as usize, i + 1);
+        assert_eq!(crate::runner::solution(solution.day()).unwrap().day(), solution.day());
+    }
+    assert_eq!(registry().len(), DAYS.count());
+}
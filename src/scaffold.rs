@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::runner::DAYS;
+use crate::utils::AOCResult;
+use crate::aocbail;
+
+// Copied to src/dayN.rs with DAY_NUMBER and DAY_NAME filled in.
+const TEMPLATE: &str = include_str!("template.rs");
+
+// The files that declare day modules and register them, relative to src/.
+// Both keep one entry per line, ordered by day.
+pub const MODULES: &str = "lib.rs";
+pub const REGISTRY: &str = "runner.rs";
+
+pub fn render(day: u8, name: &str) -> String {
+    TEMPLATE
+        .replace("DAY_NUMBER", &day.to_string())
+        .replace("DAY_NAME", name)
+}
+
+fn module_day(line: &str) -> Option<u8> {
+    line.trim().strip_prefix("pub mod day")?.strip_suffix(';')?.parse().ok()
+}
+
+fn registry_day(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix("&Registered::<crate::day")?;
+    rest[..rest.find("::")?].parse().ok()
+}
+
+// Inserts `entry` among the lines `day_of` recognises so they stay ordered
+// by day. Returns None when there is nowhere sensible to put it.
+fn insert_ordered(
+    contents: &str,
+    day: u8,
+    entry: &str,
+    day_of: impl Fn(&str) -> Option<u8>,
+) -> AOCResult<Option<String>> {
+    let mut lines = contents.lines().collect::<Vec<_>>();
+    let days = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, day_of(line)?)))
+        .collect::<Vec<_>>();
+    if days.iter().any(|(_, d)| *d == day) {
+        aocbail!("Day {} is already registered", day);
+    }
+
+    let index = match days.iter().rev().find(|(_, d)| *d < day) {
+        Some((i, _)) => i + 1,
+        None => match days.first() {
+            Some((i, _)) => *i,
+            None => return Ok(None),
+        },
+    };
+    lines.insert(index, entry);
+
+    let mut patched = lines.join("\n");
+    if contents.ends_with('\n') {
+        patched.push('\n');
+    }
+    Ok(Some(patched))
+}
+
+fn patch(path: &Path, day: u8, entry: &str, day_of: impl Fn(&str) -> Option<u8>) -> AOCResult<String> {
+    let contents = fs::read_to_string(path)?;
+    match insert_ordered(&contents, day, entry, day_of)? {
+        Some(patched) => Ok(patched),
+        None => aocbail!("Unable to find where to register day {} in {}", day, path.display()),
+    }
+}
+
+// Generates src/dayN.rs from the template, declares and registers it, and
+// creates empty input files for it. Existing inputs are left alone.
+// Returns every file that was created or changed.
+pub fn new_day(src: &Path, input: &Path, day: u8, name: &str) -> AOCResult<Vec<PathBuf>> {
+    if !DAYS.contains(&day) {
+        aocbail!("Day {} is outside {}-{}", day, DAYS.start(), DAYS.end());
+    }
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+        aocbail!("Day names must be snake_case, got '{}'", name);
+    }
+    let module = src.join(format!("day{}.rs", day));
+    if module.exists() {
+        aocbail!("{} already exists", module.display());
+    }
+
+    // Patch everything in memory first so a failure leaves the tree untouched.
+    let modules = src.join(MODULES);
+    let registry = src.join(REGISTRY);
+    let patched_modules = patch(&modules, day, &format!("pub mod day{};", day), module_day)?;
+    let patched_registry = patch(
+        &registry,
+        day,
+        &format!("    &Registered::<crate::day{0}::Day{0}>(PhantomData),", day),
+        registry_day,
+    )?;
+
+    fs::write(&module, render(day, name))?;
+    fs::write(&modules, patched_modules)?;
+    fs::write(&registry, patched_registry)?;
+    let mut changed = vec![module, modules, registry];
+
+    fs::create_dir_all(input)?;
+    for file in [format!("day{}", day), format!("test_day{}", day)] {
+        let path = input.join(file);
+        if !path.exists() {
+            fs::write(&path, "")?;
+            changed.push(path);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scaffold::*;
+
+    #[test]
+    pub fn test_new_day() {
+        let root = crate::utils::test_dir("scaffold");
+        let (src, input) = (root.join("src"), root.join("input"));
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join(MODULES), "pub mod utils;\npub mod day1;\npub mod day3;\n").unwrap();
+        fs::write(
+            src.join(REGISTRY),
+            "static REGISTRY: &[&dyn Day] = &[\n    &Registered::<crate::day1::Day1>(PhantomData),\n    &Registered::<crate::day3::Day3>(PhantomData),\n];\n",
+        )
+        .unwrap();
+
+        let changed = new_day(&src, &input, 2, "two_step").unwrap();
+        assert_eq!(changed.len(), 5);
+        let module = fs::read_to_string(src.join("day2.rs")).unwrap();
+        assert!(module.contains("pub struct Day2;"));
+        assert!(module.contains("const NAME: &'static str = \"two_step\";"));
+        assert!(module.contains("Input::from(\"test_day2\")"));
+        assert!(fs::read_to_string(src.join(MODULES)).unwrap().contains("pub mod day1;\npub mod day2;\npub mod day3;\n"));
+        assert!(fs::read_to_string(src.join(REGISTRY)).unwrap().contains("Day1>(PhantomData),\n    &Registered::<crate::day2::Day2>(PhantomData),\n"));
+        assert_eq!(fs::read_to_string(input.join("test_day2")).unwrap(), "");
+
+        assert!(new_day(&src, &input, 2, "two_step").is_err());
+    }
+
+    #[test]
+    pub fn test_template_imports() {
+        // Every name a new day imports has to be used, or the day fails
+        // clippy's unused_imports before anyone has touched it.
+        let module = render(26, "crab_cups");
+        let (imports, body): (Vec<_>, Vec<_>) = module.lines().partition(|line| line.starts_with("use "));
+        let body = body.join("\n");
+        for import in imports {
+            let names = import.trim_start_matches("use ").trim_end_matches(';');
+            let names = names.rsplit_once('{').map_or(names, |(_, names)| names.trim_end_matches('}'));
+            for name in names.split(',').map(|name| name.trim().rsplit("::").next().unwrap()) {
+                let used = regex::Regex::new(&format!(r"\b{}\b", name)).unwrap();
+                assert!(used.is_match(&body), "{} is imported but never used", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::solution::Day;
+use crate::utils::{self, AOCResult, Input};
+use crate::verify::{self, Expected};
+use crate::{aocbail, runner};
+
+// Snapshots are checked in next to the sources, one file per input, named
+// after the input they were taken from.
+pub fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inputs {
+    Examples,
+    Real,
+    All,
+}
+
+// One input of a day and the parts it is meant for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub day: u8,
+    pub input: String,
+    pub parts: Vec<u8>,
+}
+
+// The answer manifest already says which inputs each day has and which
+// parts they are for; several examples only make sense for one part (and
+// day14's first example would need 2^34 addresses for part 2).
+pub fn cases(manifest: &[Expected], days: &[u8], which: Inputs) -> Vec<Case> {
+    let mut cases: Vec<Case> = Vec::new();
+    for expected in manifest.iter().filter(|e| days.contains(&e.day)) {
+        let real = expected.input == format!("day{}", expected.day);
+        let wanted = match which {
+            Inputs::Examples => !real,
+            Inputs::Real => real,
+            Inputs::All => true,
+        };
+        if !wanted {
+            continue;
+        }
+        match cases.iter_mut().find(|c| c.day == expected.day && c.input == expected.input) {
+            Some(case) if !case.parts.contains(&expected.part) => case.parts.push(expected.part),
+            Some(_) => {}
+            None => cases.push(Case {
+                day: expected.day,
+                input: expected.input.clone(),
+                parts: vec![expected.part],
+            }),
+        }
+    }
+    for case in cases.iter_mut() {
+        case.parts.sort_unstable();
+    }
+    cases.sort_by(|a, b| (a.day, &a.input).cmp(&(b.day, &b.input)));
+    cases
+}
+
+// Everything a day has to say about an input: each part's answer or
+// error, followed by its picture if it draws one.
+pub fn render(solution: &dyn Day, case: &Case) -> String {
+    let model = match solution.parse(&Input::from(case.input.as_str())) {
+        Ok(model) => model,
+        Err(e) => return format!("parse error: {}\n", e),
+    };
+
+    let mut out = String::new();
+    for part in case.parts.iter().copied() {
+        match solution.part(part, model.as_ref()) {
+            Ok(answer) => writeln!(out, "part {}: {}", part, answer),
+            Err(e) => writeln!(out, "part {}: error: {}", part, e),
+        }
+        .unwrap();
+        match solution.picture(part, model.as_ref()) {
+            Ok(Some(picture)) => write!(out, "part {} picture:\n{}", part, picture),
+            Ok(None) => Ok(()),
+            Err(e) => writeln!(out, "part {} picture: error: {}", part, e),
+        }
+        .unwrap();
+    }
+    out
+}
+
+// The lines that differ between two snapshots, as "-expected"/"+actual"
+// pairs with line numbers. Enough to spot what changed, not a real diff.
+pub fn diff(expected: &str, actual: &str) -> Vec<String> {
+    let (expected, actual) = (expected.lines().collect::<Vec<_>>(), actual.lines().collect::<Vec<_>>());
+    let mut lines = Vec::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let (before, after) = (expected.get(i), actual.get(i));
+        if before != after {
+            if let Some(before) = before {
+                lines.push(format!("{:>4} -{}", i + 1, before));
+            }
+            if let Some(after) = after {
+                lines.push(format!("{:>4} +{}", i + 1, after));
+            }
+        }
+    }
+    lines
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Match,
+    Missing,
+    Differs(Vec<String>),
+    Written,
+}
+
+// Compares a case's output with its snapshot. With `update` a missing or
+// different snapshot is rewritten instead of reported.
+pub fn check(solution: &dyn Day, case: &Case, update: bool) -> AOCResult<Outcome> {
+    let actual = render(solution, case);
+    let path = snapshot_dir().join(&case.input);
+    let outcome = match std::fs::read_to_string(&path) {
+        Ok(expected) if expected == actual => return Ok(Outcome::Match),
+        Ok(expected) => Outcome::Differs(diff(&expected, &actual)),
+        Err(_) => Outcome::Missing,
+    };
+    if !update {
+        return Ok(outcome);
+    }
+    std::fs::create_dir_all(snapshot_dir())?;
+    std::fs::write(&path, actual)?;
+    Ok(Outcome::Written)
+}
+
+// Checks (or with `update`, rewrites) the snapshots of every input of
+// `days` in the manifest, spread over `jobs` threads, and returns how many
+// failed.
+pub fn run(days: &[u8], which: Inputs, update: bool, manifest: &Path, jobs: usize) -> AOCResult<usize> {
+    let cases = cases(&verify::load_manifest(manifest)?, days, which);
+    if cases.is_empty() {
+        aocbail!("No inputs to snapshot in {}", manifest.display());
+    }
+    let solutions = cases.iter().map(|case| runner::solution(case.day)).collect::<AOCResult<Vec<_>>>()?;
+
+    let work = solutions.iter().zip(cases.iter()).collect::<Vec<_>>();
+    let outcomes = utils::map_parallel(&work, jobs, |(solution, case)| check(**solution, case, update));
+    let mut failed = 0;
+    for (case, outcome) in cases.iter().zip(outcomes) {
+        let (ok, status) = match outcome {
+            Some(Ok(Outcome::Match)) => (true, "ok".to_owned()),
+            Some(Ok(Outcome::Written)) => (true, "updated".to_owned()),
+            Some(Ok(Outcome::Missing)) => (false, "MISSING  (record it with `aoc2020 snapshot --update`)".to_owned()),
+            Some(Ok(Outcome::Differs(lines))) => (false, format!("DIFF\n{}", lines.join("\n"))),
+            Some(Err(e)) => (false, format!("ERROR  {}", e)),
+            None => (false, "PANICKED".to_owned()),
+        };
+        if !ok {
+            failed += 1;
+        }
+        println!("{:>3}  {:<16} {}", case.day, case.input, status);
+    }
+    Ok(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::DAYS;
+    use crate::snapshot::*;
+
+    #[test]
+    pub fn test_snapshot() {
+        let manifest = verify::parse_manifest("14 2 test_day14_2 208\n14 1 day14 1\n14 1 test_day14 165\n14 2 day14 2\n").unwrap();
+        let examples = cases(&manifest, &[14], Inputs::Examples);
+        assert_eq!(examples.iter().map(|c| c.input.as_str()).collect::<Vec<_>>(), vec!["test_day14", "test_day14_2"]);
+        assert_eq!(cases(&manifest, &[14], Inputs::Real)[0].parts, vec![1, 2]);
+        assert!(cases(&manifest, &[1], Inputs::All).is_empty());
+
+        assert_eq!(diff("a\nb\n", "a\nc\nd\n"), vec!["   2 -b", "   2 +c", "   3 +d"]);
+
+        let case = Case { day: 6, input: "test_day6".to_owned(), parts: vec![1, 2] };
+        let solution = runner::solution(6).unwrap();
+        assert_eq!(render(solution, &case), "part 1: 11\npart 2: 6\n");
+        assert_eq!(check(solution, &case, false).unwrap(), Outcome::Match);
+    }
+
+    // Every example input against its snapshot. Refresh them with
+    // `cargo run -- snapshot all --update` after an intended change.
+    #[test]
+    pub fn test_snapshot_examples() {
+        let days = DAYS.collect::<Vec<_>>();
+        let failed = run(&days, Inputs::Examples, false, &verify::manifest_path(), utils::default_jobs());
+        assert_eq!(failed.unwrap(), 0);
+    }
+
+    // The real inputs take a while in a debug build, so they only run when
+    // asked for: `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    pub fn test_snapshot_real() {
+        let days = DAYS.collect::<Vec<_>>();
+        let failed = run(&days, Inputs::Real, false, &verify::manifest_path(), utils::default_jobs());
+        assert_eq!(failed.unwrap(), 0);
+    }
+}
@@ -0,0 +1,180 @@
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::animate::Animation;
+use crate::utils::{AOCResult, Input, Rng, SpanExt};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    // The day has no such part, e.g. day 25's part 2.
+    None,
+}
+
+impl Answer {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::Text(_) => "text",
+            Answer::None => "none",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{}", v),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::None => write!(f, "n/a"),
+        }
+    }
+}
+
+macro_rules! int_answer {
+    ($($type:ty),*) => {
+        $(impl From<$type> for Answer {
+            fn from(v: $type) -> Self {
+                Answer::Int(v as i64)
+            }
+        })*
+    };
+}
+int_answer!(i32, i64, u32, u64, usize);
+
+// A part that doesn't exist answers `()`.
+impl From<()> for Answer {
+    fn from(_: ()) -> Self {
+        Answer::None
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+// Every day parses its input once into a model, and both parts work off
+// a shared reference to it. Parts that need to mutate (simulations, the
+// day8 VM) clone the model first so parse can be timed on its own. A day
+// without a part 2 has `type Part2 = ()`.
+pub trait Solution {
+    type Model;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    const DAY: u8;
+    const NAME: &'static str;
+
+    fn parse(input: &Input) -> AOCResult<Self::Model>;
+    fn part1(model: &Self::Model) -> AOCResult<Self::Part1>;
+    fn part2(model: &Self::Model) -> AOCResult<Self::Part2>;
+
+    // Simulating days can show their work step by step under `--animate`.
+    fn animate(_model: &Self::Model, _part: u8) -> Option<Box<dyn Animation>> {
+        None
+    }
+
+    // A text drawing of the state `part` ends in, which `export` paints.
+    fn picture(_model: &Self::Model, _part: u8) -> AOCResult<Option<String>> {
+        Ok(None)
+    }
+
+    // A random input that is valid for both parts, for stress tests and
+    // `gen`. What `size` counts is up to the day; None means about as big
+    // as a real input.
+    fn generate(_rng: &mut Rng, _size: Option<usize>) -> Option<String> {
+        None
+    }
+}
+
+// Object-safe view of a Solution so days with different models can sit
+// side by side in the registry.
+pub trait Day: Sync {
+    fn day(&self) -> u8;
+    fn name(&self) -> &'static str;
+    fn parse(&self, input: &Input) -> AOCResult<Box<dyn Any>>;
+    fn part1(&self, model: &dyn Any) -> AOCResult<Answer>;
+    fn part2(&self, model: &dyn Any) -> AOCResult<Answer>;
+    fn animate(&self, part: u8, model: &dyn Any) -> Option<Box<dyn Animation>>;
+    fn picture(&self, part: u8, model: &dyn Any) -> AOCResult<Option<String>>;
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String>;
+    // False for a part the day doesn't have, whose answer is Answer::None.
+    fn has_part(&self, part: u8) -> bool;
+
+    fn default_input(&self) -> Input {
+        Input::Named(format!("day{}", self.day()))
+    }
+
+    fn part(&self, part: u8, model: &dyn Any) -> AOCResult<Answer> {
+        if part == 1 {
+            self.part1(model)
+        } else {
+            self.part2(model)
+        }
+    }
+}
+
+pub struct Registered<S>(pub PhantomData<fn() -> S>);
+
+impl<S: Solution> Registered<S> {
+    fn model<'a>(&self, model: &'a dyn Any) -> &'a S::Model
+    where
+        S::Model: 'static,
+    {
+        model
+            .downcast_ref::<S::Model>()
+            .expect("model was parsed by a different day")
+    }
+}
+
+impl<S: Solution> Day for Registered<S>
+where
+    S::Model: 'static,
+    S::Part2: 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn parse(&self, input: &Input) -> AOCResult<Box<dyn Any>> {
+        Ok(Box::new(S::parse(input).in_input(input)?))
+    }
+
+    fn part1(&self, model: &dyn Any) -> AOCResult<Answer> {
+        S::part1(self.model(model)).map(Into::into)
+    }
+
+    fn part2(&self, model: &dyn Any) -> AOCResult<Answer> {
+        S::part2(self.model(model)).map(Into::into)
+    }
+
+    fn animate(&self, part: u8, model: &dyn Any) -> Option<Box<dyn Animation>> {
+        S::animate(self.model(model), part)
+    }
+
+    fn picture(&self, part: u8, model: &dyn Any) -> AOCResult<Option<String>> {
+        S::picture(self.model(model), part)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        S::generate(rng, size)
+    }
+
+    fn has_part(&self, part: u8) -> bool {
+        part == 1 || TypeId::of::<S::Part2>() != TypeId::of::<()>()
+    }
+}
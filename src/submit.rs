@@ -0,0 +1,288 @@
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::utils::{self, AOCError, AOCResult};
+use crate::{aocbail, regex};
+
+lazy_static! {
+    static ref ARTICLE_RE: Regex = regex!(r"(?s)<article[^>]*>(.*?)</article>");
+    static ref TAG_RE: Regex = regex!(r"<[^>]*>");
+    static ref WAIT_RE: Regex = regex!(r"You have (?:(\d+)m )?(\d+)s left to wait");
+}
+
+// Every submission is appended to this file in the input directory.
+pub const LOG: &str = "submissions";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited(Duration),
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl Verdict {
+    // How a verdict is written to the submission log.
+    fn tag(&self) -> String {
+        match self {
+            Verdict::Correct => "correct".to_owned(),
+            Verdict::TooHigh => "too_high".to_owned(),
+            Verdict::TooLow => "too_low".to_owned(),
+            Verdict::Wrong => "wrong".to_owned(),
+            Verdict::RateLimited(wait) => format!("wait:{}", wait.as_secs()),
+            Verdict::AlreadySolved => "solved".to_owned(),
+            Verdict::Unknown(_) => "unknown".to_owned(),
+        }
+    }
+
+    fn from_tag(tag: &str) -> AOCResult<Verdict> {
+        Ok(match tag {
+            "correct" => Verdict::Correct,
+            "too_high" => Verdict::TooHigh,
+            "too_low" => Verdict::TooLow,
+            "wrong" => Verdict::Wrong,
+            "solved" => Verdict::AlreadySolved,
+            "unknown" => Verdict::Unknown(String::new()),
+            _ => match tag.strip_prefix("wait:") {
+                Some(secs) => Verdict::RateLimited(Duration::from_secs(secs.parse()?)),
+                None => aocbail!("Unknown verdict '{}' in submission log", tag),
+            },
+        })
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited(wait) => write!(f, "rate limited, wait {}s", wait.as_secs()),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unknown(message) => write!(f, "unrecognised response: {}", message),
+        }
+    }
+}
+
+// Reads the verdict out of the response page's <article>.
+pub fn classify(html: &str) -> Verdict {
+    let message = match ARTICLE_RE.captures(html) {
+        Some(article) => TAG_RE.replace_all(&article[1], "").trim().to_owned(),
+        None => TAG_RE.replace_all(html, "").trim().to_owned(),
+    };
+
+    if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("your answer is too high") {
+        Verdict::TooHigh
+    } else if message.contains("your answer is too low") {
+        Verdict::TooLow
+    } else if message.contains("That's not the right answer") {
+        Verdict::Wrong
+    } else if let Some(wait) = WAIT_RE.captures(&message) {
+        let minutes = wait.get(1).map_or(0, |m| m.as_str().parse::<u64>().unwrap_or(0));
+        let seconds = wait[2].parse::<u64>().unwrap_or(0);
+        Verdict::RateLimited(Duration::from_secs(minutes * 60 + seconds))
+    } else if message.contains("You gave an answer too recently") {
+        Verdict::RateLimited(Duration::from_secs(60))
+    } else if message.contains("Did you already complete it") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub time: u64,
+    pub day: u8,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub struct Submitter {
+    pub base_url: String,
+    pub session: String,
+    pub log: PathBuf,
+}
+
+impl Submitter {
+    // None when there is no session token to authenticate with.
+    pub fn from_env() -> Option<Submitter> {
+        Some(Submitter {
+            base_url: utils::base_url(),
+            session: utils::session()?,
+            log: utils::input_dir().join(LOG),
+        })
+    }
+
+    // The log is plain text, one "<unix time> <day> <part> <verdict> <answer>" per line.
+    pub fn history(&self) -> AOCResult<Vec<Record>> {
+        if !self.log.exists() {
+            return Ok(Vec::new());
+        }
+        let mut records = Vec::new();
+        for line in std::fs::read_to_string(&self.log)?.lines() {
+            let fields = line.splitn(5, ' ').collect::<Vec<_>>();
+            if fields.len() != 5 {
+                aocbail!("Malformed submission record '{}' in {}", line, self.log.display());
+            }
+            records.push(Record {
+                time: fields[0].parse()?,
+                day: fields[1].parse()?,
+                part: fields[2].parse()?,
+                verdict: Verdict::from_tag(fields[3])?,
+                answer: fields[4].to_owned(),
+            });
+        }
+        Ok(records)
+    }
+
+    fn record(&self, record: &Record) -> AOCResult<()> {
+        let mut log = OpenOptions::new().create(true).append(true).open(&self.log)?;
+        writeln!(
+            log,
+            "{} {} {} {} {}",
+            record.time,
+            record.day,
+            record.part,
+            record.verdict.tag(),
+            record.answer
+        )?;
+        Ok(())
+    }
+
+    // Refuses submissions the history already answers: a solved part, a
+    // repeated wrong answer, one outside known bounds, or one sent while a
+    // rate limit is still running.
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> AOCResult<()> {
+        let history = self.history()?;
+        let value = answer.parse::<i64>().ok();
+        for record in history.iter().filter(|r| r.day == day && r.part == part) {
+            let bounds = value.zip(record.answer.parse::<i64>().ok());
+            match &record.verdict {
+                Verdict::Correct | Verdict::AlreadySolved => {
+                    aocbail!("Day {} part {} is already solved", day, part)
+                }
+                verdict if verdict.is_wrong() && record.answer == answer => {
+                    aocbail!("{} was already submitted for day {} part {}: {}", answer, day, part, verdict)
+                }
+                Verdict::TooHigh if bounds.is_some_and(|(v, high)| v >= high) => {
+                    aocbail!("{} is at least {}, which was already too high", answer, record.answer)
+                }
+                Verdict::TooLow if bounds.is_some_and(|(v, low)| v <= low) => {
+                    aocbail!("{} is at most {}, which was already too low", answer, record.answer)
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(limit) = history.iter().rev().find_map(|r| match r.verdict {
+            Verdict::RateLimited(wait) => Some(r.time + wait.as_secs()),
+            _ => None,
+        }) {
+            if limit > now() {
+                aocbail!("Rate limited, wait another {}s before submitting", limit - now());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> AOCResult<Verdict> {
+        if answer.is_empty() || answer.contains(char::is_whitespace) {
+            aocbail!("Refusing to submit '{}'", answer);
+        }
+        self.check(day, part, answer)?;
+
+        let url = format!("{}/day/{}/answer", self.base_url.trim_end_matches('/'), day);
+        let html = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", utils::USER_AGENT)
+            .timeout(utils::TIMEOUT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| AOCError {
+                e: anyhow!("Unable to submit day {} part {}: {}", day, part, e),
+                span: None,
+            })?
+            .into_string()?;
+
+        let verdict = classify(&html);
+        if !matches!(verdict, Verdict::Unknown(_)) {
+            self.record(&Record {
+                time: now(),
+                day,
+                part,
+                verdict: verdict.clone(),
+                answer: answer.to_owned(),
+            })?;
+        }
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::submit::*;
+
+    fn page(message: &str) -> String {
+        format!("<html><main><article><p>{}</p></article></main></html>", message)
+    }
+
+    #[test]
+    pub fn test_submit() {
+        assert_eq!(
+            classify(&page("That's not the right answer; your answer is too high.  <a href=\"/2020/day/1\">[Return]</a>")),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            classify(&page("You gave an answer too recently. You have 4m 30s left to wait.")),
+            Verdict::RateLimited(Duration::from_secs(270))
+        );
+        assert_eq!(classify(&page("<span>That's the right answer!</span>")), Verdict::Correct);
+
+        let (base_url, server) = utils::mock::serve(vec![
+            (200, page("That's not the right answer; your answer is too low.")),
+            (200, page("That's the right answer! You are one gold star closer.")),
+        ]);
+        let dir = utils::test_dir("submit");
+        let submitter = Submitter {
+            base_url,
+            session: "cookie".to_owned(),
+            log: dir.join("submissions"),
+        };
+
+        assert_eq!(submitter.submit(1, 2, "100").unwrap(), Verdict::TooLow);
+        // Neither reaches the server: one is a repeat, the other is known to be too low.
+        assert!(submitter.submit(1, 2, "100").is_err());
+        assert!(submitter.submit(1, 2, "99").is_err());
+        assert_eq!(submitter.submit(1, 2, "200").unwrap(), Verdict::Correct);
+        assert!(submitter.submit(1, 2, "300").is_err());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /day/1/answer "));
+        assert!(requests[0].ends_with("level=2&answer=100"));
+        assert_eq!(submitter.history().unwrap().len(), 2);
+    }
+}
@@ -1,18 +1,31 @@
-#![allow(dead_code)]
+use crate::{aocbail, solution::Solution, utils};
+use utils::{AOCResult, Input};
 
-use crate::{aocbail, utils};
-use utils::{AOCResult, AOCError};
+pub struct DayDAY_NUMBER;
 
-pub fn day_template() {
-    let input = utils::get_input("day10");
-    println!("part 1: {:?}", input);
+impl Solution for DayDAY_NUMBER {
+    type Model = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u8 = DAY_NUMBER;
+    const NAME: &'static str = "DAY_NAME";
+
+    fn parse(input: &Input) -> AOCResult<Vec<String>> {
+        Ok(utils::get_input(input)?.collect())
+    }
+
+    fn part1(_lines: &Vec<String>) -> AOCResult<usize> {
+        aocbail!("DAY_NAME part 1 is not implemented")
+    }
+
+    fn part2(_lines: &Vec<String>) -> AOCResult<usize> {
+        aocbail!("DAY_NAME part 2 is not implemented")
+    }
 }
 
 #[test]
-pub fn test_template() {
-    let input = utils::get_input("test_day10");
-    assert_eq!(
-        127,
-        127
-    );
+pub fn test_dayDAY_NUMBER() {
+    let lines = DayDAY_NUMBER::parse(&Input::from("test_dayDAY_NUMBER")).unwrap();
+    assert_eq!(DayDAY_NUMBER::part1(&lines).unwrap(), 0);
 }
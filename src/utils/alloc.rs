@@ -0,0 +1,153 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+use super::format_bytes;
+
+// Wraps the system allocator and keeps count of what each thread allocates.
+// It's only installed with the count-allocs feature; otherwise nothing is
+// counted and `measure` reports zeros.
+pub struct Counting;
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+pub fn counting() -> bool {
+    cfg!(feature = "count-allocs")
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Counters {
+    count: u64,
+    bytes: u64,
+    live: u64,
+    peak: u64,
+}
+
+// Per thread, since a day's phases all run on the thread solving it while
+// other days run next to it. Const-initialised, so reading them never
+// allocates.
+thread_local! {
+    static COUNTERS: Cell<Counters> = const { Cell::new(Counters { count: 0, bytes: 0, live: 0, peak: 0 }) };
+}
+
+fn update(f: impl FnOnce(&mut Counters)) {
+    // Threads being torn down have lost their counters already.
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        f(&mut c);
+        c.peak = c.peak.max(c.live);
+        counters.set(c);
+    });
+}
+
+fn counters() -> Counters {
+    COUNTERS.try_with(Cell::get).unwrap_or_default()
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            update(|c| {
+                c.count += 1;
+                c.bytes += layout.size() as u64;
+                c.live += layout.size() as u64;
+            });
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            update(|c| {
+                c.count += 1;
+                c.bytes += layout.size() as u64;
+                c.live += layout.size() as u64;
+            });
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        update(|c| c.live = c.live.saturating_sub(layout.size() as u64));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            update(|c| {
+                c.count += 1;
+                c.bytes += new_size as u64;
+                c.live = c.live.saturating_sub(layout.size() as u64) + new_size as u64;
+            });
+        }
+        new
+    }
+}
+
+// What one phase allocated: how many allocations (growing counts as one),
+// their total size, and the most it held at once on top of what was live
+// before it started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Allocs {
+    pub count: u64,
+    pub bytes: u64,
+    pub peak: u64,
+}
+
+impl fmt::Display for Allocs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} allocs, {} allocated, {} peak", self.count, format_bytes(self.bytes), format_bytes(self.peak))
+    }
+}
+
+// Runs `f`, counting what it allocates on this thread.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (Allocs, T) {
+    let before = counters();
+    update(|c| c.peak = c.live);
+    let value = f();
+    let after = counters();
+    update(|c| c.peak = c.peak.max(before.peak));
+    let allocs = Allocs {
+        count: after.count - before.count,
+        bytes: after.bytes - before.bytes,
+        peak: after.peak.saturating_sub(before.live),
+    };
+    (allocs, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::alloc::*;
+
+    #[test]
+    pub fn test_alloc() {
+        // Drives the allocator by hand, so this works with or without it
+        // installed; anything the test harness allocates meanwhile only
+        // counts when it is.
+        let layout = Layout::from_size_align(1000, 8).unwrap();
+        let (allocs, ()) = measure(|| unsafe {
+            let a = Counting.alloc(layout);
+            let b = Counting.alloc_zeroed(layout);
+            Counting.dealloc(a, layout);
+            let b = Counting.realloc(b, layout, 3000);
+            Counting.dealloc(b, Layout::from_size_align(3000, 8).unwrap());
+        });
+        if !counting() {
+            assert_eq!(allocs, Allocs { count: 3, bytes: 5000, peak: 3000 });
+        }
+        assert!(allocs.count >= 3 && allocs.bytes >= 5000 && allocs.peak >= 3000);
+
+        let (outer, (inner, kept)) = measure(|| measure(|| vec![0u8; 4096]));
+        if counting() {
+            assert_eq!((inner.count, inner.peak), (1, 4096));
+            assert!(outer.peak >= 4096);
+        }
+        drop(kept);
+        assert_eq!(Allocs { count: 2, bytes: 2048, peak: 1536 }.to_string(), "2 allocs, 2.00KiB allocated, 1.50KiB peak");
+    }
+}
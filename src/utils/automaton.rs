@@ -0,0 +1,233 @@
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+use super::{Glyph, Grid2D};
+
+// Something that evolves one generation at a time.
+pub trait Automaton {
+    // Advances one generation, returning whether anything changed.
+    fn step(&mut self) -> bool;
+
+    fn run_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    // Steps until a generation changes nothing, returning how many
+    // generations did change something. Never returns for automata that
+    // keep growing.
+    fn run_until_stable(&mut self) -> usize {
+        let mut generations = 0;
+        while self.step() {
+            generations += 1;
+        }
+        generations
+    }
+}
+
+// A bounded 2D automaton where every cell has a state. Each cell watches a
+// fixed set of others, worked out once up front, and its next state
+// depends on how many of those are live.
+#[derive(Clone)]
+pub struct Dense<T> {
+    cells: Grid2D<T>,
+    neighbours: Grid2D<Vec<(usize, usize)>>,
+    live: fn(&T) -> bool,
+    rule: fn(&T, usize) -> T,
+}
+
+impl<T: Clone + PartialEq> Dense<T> {
+    pub fn new(
+        cells: Grid2D<T>,
+        neighbourhood: impl Fn(&Grid2D<T>, (usize, usize)) -> Vec<(usize, usize)>,
+        live: fn(&T) -> bool,
+        rule: fn(&T, usize) -> T,
+    ) -> Self {
+        let mut neighbours = cells.map(|_| Vec::new());
+        for position in cells.positions() {
+            neighbours[position] = neighbourhood(&cells, position);
+        }
+        Dense {
+            cells,
+            neighbours,
+            live,
+            rule,
+        }
+    }
+
+    pub fn cells(&self) -> &Grid2D<T> {
+        &self.cells
+    }
+
+    pub fn into_cells(self) -> Grid2D<T> {
+        self.cells
+    }
+
+    pub fn count_live(&self) -> usize {
+        self.cells.count(self.live)
+    }
+}
+
+impl<T: Clone + PartialEq> Automaton for Dense<T> {
+    fn step(&mut self) -> bool {
+        let mut next = self.cells.clone();
+        let mut changed = false;
+        for (position, cell) in self.cells.iter() {
+            let live = self.neighbours[position]
+                .iter()
+                .filter(|neighbour| (self.live)(&self.cells[**neighbour]))
+                .count();
+            let state = (self.rule)(cell, live);
+            if state != *cell {
+                changed = true;
+                next[position] = state;
+            }
+        }
+        self.cells = next;
+        changed
+    }
+}
+
+impl<T: Glyph> fmt::Display for Dense<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.cells, f)
+    }
+}
+
+// Coordinates on an unbounded lattice.
+pub trait Lattice: Copy + Eq + Hash {
+    fn offset(self, by: Self) -> Self;
+}
+
+impl<const N: usize> Lattice for [i32; N] {
+    fn offset(mut self, by: Self) -> Self {
+        for (c, d) in self.iter_mut().zip(by.iter()) {
+            *c += d;
+        }
+        self
+    }
+}
+
+impl Lattice for (i32, i32) {
+    fn offset(self, by: Self) -> Self {
+        (self.0 + by.0, self.1 + by.1)
+    }
+}
+
+// The six neighbours of a hex cell in doubled coordinates, where east and
+// west are two apart in x and the diagonals one apart in each.
+pub const HEX: [(i32, i32); 6] = [(2, 0), (1, 1), (-1, 1), (-2, 0), (-1, -1), (1, -1)];
+
+// Every other cell within one step along each axis of an N-D lattice.
+pub fn moore<const N: usize>() -> Vec<[i32; N]> {
+    std::iter::repeat_n(-1..=1, N)
+        .multi_cartesian_product()
+        .map(|delta| {
+            let mut offset = [0; N];
+            offset.copy_from_slice(&delta);
+            offset
+        })
+        .filter(|offset| offset.iter().any(|d| *d != 0))
+        .collect()
+}
+
+// An unbounded automaton of cells that are either live or not, storing
+// only the live ones. `rule` decides whether a cell is live next
+// generation from whether it is now and how many live neighbours it has.
+#[derive(Clone)]
+pub struct Sparse<C: Lattice> {
+    live: HashSet<C>,
+    offsets: Vec<C>,
+    rule: fn(bool, usize) -> bool,
+}
+
+impl<C: Lattice> Sparse<C> {
+    pub fn new(live: HashSet<C>, offsets: Vec<C>, rule: fn(bool, usize) -> bool) -> Self {
+        Sparse { live, offsets, rule }
+    }
+
+    pub fn live(&self) -> &HashSet<C> {
+        &self.live
+    }
+
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+}
+
+impl<const N: usize> Sparse<[i32; N]> {
+    pub fn lattice(live: HashSet<[i32; N]>, rule: fn(bool, usize) -> bool) -> Self {
+        Sparse::new(live, moore(), rule)
+    }
+}
+
+impl Sparse<(i32, i32)> {
+    pub fn hex(live: HashSet<(i32, i32)>, rule: fn(bool, usize) -> bool) -> Self {
+        Sparse::new(live, HEX.to_vec(), rule)
+    }
+}
+
+impl<C: Lattice> Automaton for Sparse<C> {
+    fn step(&mut self) -> bool {
+        // Live cells with no live neighbours still get a say.
+        let mut counts: HashMap<C, usize> = self.live.iter().map(|cell| (*cell, 0)).collect();
+        for cell in self.live.iter() {
+            for offset in self.offsets.iter() {
+                *counts.entry(cell.offset(*offset)).or_insert(0) += 1;
+            }
+        }
+
+        let next = counts
+            .into_iter()
+            .filter(|(cell, count)| (self.rule)(self.live.contains(cell), *count))
+            .map(|(cell, _)| cell)
+            .collect::<HashSet<_>>();
+        let changed = next != self.live;
+        self.live = next;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::automaton::*;
+    use crate::utils::{pixel, Lines};
+
+    fn life(live: bool, neighbours: usize) -> bool {
+        neighbours == 3 || (live && neighbours == 2)
+    }
+
+    #[test]
+    pub fn test_automaton() {
+        let rows = vec![".....", "..#..", "..#..", "..#..", "....."];
+        let mut input = Lines::new("blinker", rows.into_iter().map(str::to_owned).collect());
+        let grid = Grid2D::parse(&mut input, pixel).unwrap();
+
+        let mut dense = Dense::new(grid.clone(), |g, p| g.neighbours8(p).collect(), |c| *c, |c, n| life(*c, n));
+        assert!(dense.step());
+        assert_eq!(dense.cells().to_string(), ".....\n.....\n.###.\n.....\n.....\n");
+        dense.run_n(3);
+        assert_eq!(dense.cells(), &grid);
+
+        let live = grid.iter().filter(|(_, c)| **c).map(|((x, y), _)| [x as i32, y as i32]).collect();
+        let mut sparse = Sparse::lattice(live, life);
+        sparse.run_n(2);
+        assert_eq!(sparse.len(), 3);
+        assert!(sparse.live().contains(&[2, 1]));
+        assert_eq!(moore::<3>().len(), 26);
+
+        // A single block is already stable, a lone cell dies out.
+        let mut block = Sparse::lattice([[0, 0], [0, 1], [1, 0], [1, 1]].iter().copied().collect(), life);
+        assert_eq!(block.run_until_stable(), 0);
+        let mut lone = Sparse::hex([(0, 0)].iter().copied().collect(), |live, n| live && n > 0);
+        assert_eq!(lone.run_until_stable(), 1);
+        assert!(lone.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::anyhow;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::{input_dir, AOCError, AOCResult};
+
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2020";
+
+pub const USER_AGENT: &str = "github.com/Ktwu/aoc2020";
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+// The session token in $AOC_SESSION, if one is set.
+pub fn session() -> Option<String> {
+    let session = std::env::var(SESSION_ENV).ok()?;
+    Some(session.trim().to_owned()).filter(|s| !s.is_empty())
+}
+
+// $AOC_BASE_URL, falling back to the real puzzle server.
+pub fn base_url() -> String {
+    std::env::var(BASE_URL_ENV).unwrap_or_else(|_| DEFAULT_BASE_URL.to_owned())
+}
+
+// Downloads puzzle inputs into a cache directory. A cached file is never
+// downloaded again, so delete it to force a refetch.
+#[derive(Clone, Debug)]
+pub struct Fetcher {
+    pub base_url: String,
+    pub session: String,
+    pub cache_dir: PathBuf,
+}
+
+impl Fetcher {
+    // None when there is no session token to authenticate with.
+    pub fn from_env() -> Option<Fetcher> {
+        Some(Fetcher {
+            base_url: base_url(),
+            session: session()?,
+            cache_dir: input_dir(),
+        })
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{}", day))
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/day/{}/input", self.base_url.trim_end_matches('/'), day)
+    }
+
+    // Returns the cached input for `day`, downloading it first if needed.
+    pub fn fetch(&self, day: u8) -> AOCResult<PathBuf> {
+        let path = self.path(day);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let url = self.url(day);
+        let body = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .timeout(TIMEOUT)
+            .call()
+            .map_err(|e| AOCError {
+                e: anyhow!("Unable to fetch day {} input: {}", day, e),
+                span: None,
+            })?
+            .into_string()?;
+
+        // Write to a temporary file first so an interrupted download never
+        // leaves a truncated input in the cache.
+        std::fs::create_dir_all(&self.cache_dir)?;
+        let partial = self.cache_dir.join(format!(".day{}.partial", day));
+        std::fs::write(&partial, body)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+}
+
+// The day a cacheable input name like "day7" refers to.
+pub fn named_day(name: &str) -> Option<u8> {
+    name.strip_prefix("day")?.parse().ok()
+}
+
+// A stand-in for the puzzle server. Each accepted connection is answered
+// with the next canned response, and the raw requests are handed back
+// when the thread is joined.
+#[cfg(test)]
+pub mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    pub fn serve(responses: Vec<(u16, String)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                requests.push(request);
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} MOCK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::fetch::*;
+
+    #[test]
+    pub fn test_fetch() {
+        let (base_url, server) = mock::serve(vec![(200, "1\n2\n3\n".to_owned()), (404, "nope".to_owned())]);
+        let dir = crate::utils::test_dir("fetch");
+        let fetcher = Fetcher {
+            base_url,
+            session: "cookie".to_owned(),
+            cache_dir: dir.join("inputs"),
+        };
+
+        let path = fetcher.fetch(3).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        // Cached, so this must not reach the server.
+        assert_eq!(fetcher.fetch(3).unwrap(), path);
+        assert!(fetcher.fetch(4).is_err());
+        assert!(!fetcher.path(4).exists());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /day/3/input "));
+        assert!(requests[0].contains("session=cookie"));
+        assert!(requests[1].starts_with("GET /day/4/input "));
+
+        assert_eq!(named_day("day12"), Some(12));
+        assert_eq!(named_day("test_day12"), None);
+        assert_eq!(named_day("day"), None);
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use super::{AOCResult, Lines};
+use crate::aocbail;
+
+// Offsets to the 4 orthogonal and the 8 surrounding neighbours, as (dx, dy).
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub const ADJACENT: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// A rectangular grid stored row by row. Positions are (x, y) with the
+// origin in the top left corner, so y counts lines of the input.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// How a cell is drawn when a grid is displayed.
+pub trait Glyph {
+    fn glyph(&self) -> char;
+}
+
+impl Glyph for bool {
+    fn glyph(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+impl Glyph for char {
+    fn glyph(&self) -> char {
+        *self
+    }
+}
+
+// The usual '#'/'.' mapping for grids of on/off cells.
+pub fn pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+impl<T: Clone> Grid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid2D {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for y in 0..self.width {
+            for x in 0..self.height {
+                cells.push(self[(y, self.height - 1 - x)].clone());
+            }
+        }
+        Grid2D {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    // Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in self.rows() {
+            cells.extend(row.iter().rev().cloned());
+        }
+        Grid2D { cells, ..*self }
+    }
+
+    // Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in self.rows().rev() {
+            cells.extend(row.iter().cloned());
+        }
+        Grid2D { cells, ..*self }
+    }
+}
+
+impl<T> Grid2D<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> AOCResult<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            aocbail!("Row {} is {} cells wide, expected {}", y, row.len(), width);
+        }
+        Ok(Grid2D {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    // Reads lines up to a blank line or the end of the input, mapping each
+    // char to a cell. A char `cell` rejects, or a ragged row, is an error
+    // pointing at the offending line.
+    pub fn parse(input: &mut Lines, mut cell: impl FnMut(char) -> Option<T>) -> AOCResult<Self> {
+        let mut grid = Grid2D {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        };
+        while let Some(line) = input.next() {
+            if line.is_empty() {
+                break;
+            }
+            let start = grid.cells.len();
+            for (offset, c) in line.char_indices() {
+                match cell(c) {
+                    Some(value) => grid.cells.push(value),
+                    None => aocbail!(at input.span(&line, &line[offset..offset + c.len_utf8()]); "Unexpected '{}' in grid", c),
+                }
+            }
+            let width = grid.cells.len() - start;
+            if grid.height == 0 {
+                grid.width = width;
+            } else if width != grid.width {
+                aocbail!(at input.span(&line, &line); "Row is {} cells wide, expected {}", width, grid.width);
+            }
+            grid.height += 1;
+        }
+        if grid.height == 0 {
+            aocbail!("Expected a grid in {}", input.name());
+        }
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): (isize, isize)) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, (x, y): (isize, isize)) -> Option<&T> {
+        if self.contains((x, y)) {
+            Some(&self[(x as usize, y as usize)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (x, y): (isize, isize)) -> Option<&mut T> {
+        if self.contains((x, y)) {
+            Some(&mut self[(x as usize, y as usize)])
+        } else {
+            None
+        }
+    }
+
+    // Treats the grid as tiling the plane in both directions.
+    pub fn get_wrapping(&self, (x, y): (isize, isize)) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    // The in-bounds positions at the given offsets from `position`.
+    pub fn neighbours<'a>(
+        &self,
+        (x, y): (usize, usize),
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+            if nx < width && ny < height {
+                Some((nx, ny))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn neighbours4(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(position, &ORTHOGONAL)
+    }
+
+    pub fn neighbours8(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(position, &ADJACENT)
+    }
+
+    // Every position from one step past `from` along `direction` up to the
+    // edge of the grid.
+    pub fn ray(&self, from: (usize, usize), (dx, dy): (isize, isize)) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        let mut position = Some(from);
+        std::iter::from_fn(move || {
+            let (x, y) = position?;
+            position = x
+                .checked_add_signed(dx)
+                .zip(y.checked_add_signed(dy))
+                .filter(|(x, y)| *x < width && *y < height && (dx, dy) != (0, 0));
+            position
+        })
+    }
+
+    // The first position along a ray whose cell satisfies `hit`.
+    pub fn cast(
+        &self,
+        from: (usize, usize),
+        direction: (isize, isize),
+        mut hit: impl FnMut(&T) -> bool,
+    ) -> Option<(usize, usize)> {
+        self.ray(from, direction).find(|position| hit(&self[*position]))
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn count(&self, mut f: impl FnMut(&T) -> bool) -> usize {
+        self.cells.iter().filter(|cell| f(cell)).count()
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid2D<U> {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside the grid", x, y);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid2D<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside the grid", x, y);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Glyph> fmt::Display for Grid2D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().map(Glyph::glyph).collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid::*;
+
+    #[test]
+    pub fn test_grid() {
+        let mut input = Lines::new("grid", vec!["#..".to_owned(), ".#.".to_owned(), "".to_owned(), "x".to_owned()]);
+        let grid = Grid2D::parse(&mut input, pixel).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "#..\n.#.\n");
+        assert_eq!(grid.get((1, 1)), Some(&true));
+        assert_eq!(grid.get((3, 0)), None);
+        assert!(*grid.get_wrapping((-3, 2)));
+
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+        assert_eq!(grid.ray((0, 0), (1, 0)).collect::<Vec<_>>(), vec![(1, 0), (2, 0)]);
+        assert_eq!(grid.cast((0, 0), (1, 1), |c| *c), Some((1, 1)));
+        assert_eq!(grid.cast((1, 1), (1, 0), |c| *c), None);
+
+        assert_eq!(grid.rotate_clockwise().to_string(), ".#\n#.\n..\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "..#\n.#.\n");
+        assert_eq!(grid.flip_vertical().to_string(), ".#.\n#..\n");
+        assert_eq!(grid.count(|c| *c), 2);
+
+        let e = Grid2D::parse(&mut input, |c| if c == '#' { Some(true) } else { None }).err().unwrap();
+        assert_eq!(e.span().unwrap().line, Some(4));
+        assert!(Grid2D::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use std::str::FromStr;
+
+use super::{AOCError, AOCResult};
+use crate::aocbail;
+
+pub type Rgb = [u8; 3];
+
+// Which colour each glyph of a drawing is painted in. Anything without a
+// colour of its own, including the padding of ragged lines, gets the
+// background.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    colours: Vec<(char, Rgb)>,
+    background: Rgb,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            colours: vec![
+                ('#', [0x20, 0x20, 0x20]),
+                ('.', [0xe8, 0xe8, 0xe8]),
+                ('L', [0x6a, 0xa8, 0x4f]),
+                ('O', [0xd8, 0x3c, 0x28]),
+            ],
+            background: [0xff, 0xff, 0xff],
+        }
+    }
+}
+
+impl Palette {
+    pub fn colour(&self, glyph: char) -> Rgb {
+        self.colours
+            .iter()
+            .find(|(g, _)| *g == glyph)
+            .map_or(self.background, |(_, rgb)| *rgb)
+    }
+
+    pub fn set(&mut self, glyph: char, rgb: Rgb) {
+        match self.colours.iter_mut().find(|(g, _)| *g == glyph) {
+            Some(entry) => entry.1 = rgb,
+            None => self.colours.push((glyph, rgb)),
+        }
+    }
+}
+
+fn parse_rgb(hex: &str) -> AOCResult<Rgb> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        aocbail!("Expected a colour like ff8800, got '{}'", hex);
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+// Overrides on top of the default palette, as in "#=000000,O=ff0000,bg=ffffff".
+impl FromStr for Palette {
+    type Err = AOCError;
+
+    fn from_str(s: &str) -> AOCResult<Palette> {
+        let mut palette = Palette::default();
+        for entry in s.split(',').filter(|entry| !entry.is_empty()) {
+            let (glyph, rgb) = match entry.split_once('=') {
+                Some((glyph, rgb)) => (glyph, parse_rgb(rgb)?),
+                None => aocbail!("Expected 'glyph=rrggbb', got '{}'", entry),
+            };
+            let mut chars = glyph.chars();
+            match (glyph, chars.next(), chars.next()) {
+                ("bg", _, _) => palette.background = rgb,
+                (_, Some(c), None) => palette.set(c, rgb),
+                _ => aocbail!("Palette entries are a single glyph or 'bg', got '{}'", glyph),
+            }
+        }
+        Ok(palette)
+    }
+}
+
+// An RGB raster, row by row from the top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    // Paints a text drawing, one `cell` x `cell` square per char.
+    pub fn draw(text: &str, cell: usize, palette: &Palette) -> Image {
+        let lines = text.lines().map(|line| line.chars().collect::<Vec<_>>()).collect::<Vec<_>>();
+        let columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let (width, height) = (columns * cell, lines.len() * cell);
+        let mut pixels = Vec::with_capacity(width * height);
+        for line in lines.iter() {
+            let row = (0..columns)
+                .flat_map(|x| std::iter::repeat_n(line.get(x).map_or(palette.background, |c| palette.colour(*c)), cell))
+                .collect::<Vec<_>>();
+            for _ in 0..cell {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        Image { width, height, pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Binary PPM (P6).
+    pub fn ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    // An 8-bit truecolour PNG. The image data goes into stored (uncompressed)
+    // deflate blocks, which keeps the encoder to a few checksums.
+    pub fn png(&self) -> Vec<u8> {
+        let mut scanlines = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            // Filter type 0, none.
+            scanlines.push(0);
+            scanlines.extend(row.iter().flatten());
+        }
+
+        // zlib header for deflate with a 32K window and no preset dictionary.
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = scanlines.chunks(u16::MAX as usize).peekable();
+        if blocks.peek().is_none() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            zlib.push(blocks.peek().is_none() as u8);
+            zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+            zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // Bit depth 8, colour type 2 (RGB), default compression, filtering
+        // and no interlacing.
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut out, b"IHDR", &header);
+        chunk(&mut out, b"IDAT", &zlib);
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in bytes {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::image::*;
+
+    #[test]
+    pub fn test_image() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let palette = "#=ff0000,bg=000000".parse::<Palette>().unwrap();
+        assert_eq!(palette.colour('#'), [0xff, 0, 0]);
+        assert_eq!(palette.colour('?'), [0, 0, 0]);
+        assert!("##=ff0000".parse::<Palette>().is_err());
+        assert!("#=red".parse::<Palette>().is_err());
+
+        let image = Image::draw("#.\n#", 2, &palette);
+        assert_eq!((image.width(), image.height()), (4, 4));
+        let ppm = image.ppm();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(&ppm[ppm.len() - 12..], &[0xff, 0, 0, 0xff, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let png = image.png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x04\0\0\0\x04"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+        // 4 scanlines of a filter byte and 4 pixels, in a single stored block.
+        assert_eq!(&png[43..48], &[1, 52, 0, 203, 255]);
+    }
+}
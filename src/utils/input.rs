@@ -0,0 +1,272 @@
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use super::fetch::{named_day, Fetcher};
+use super::{AOCError, AOCResult, Span};
+
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+pub const PROFILE_ENV: &str = "AOC_PROFILE";
+
+lazy_static! {
+    static ref INPUT_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+    static ref PROFILE: RwLock<Option<String>> = RwLock::new(None);
+}
+
+// The lines of an input. It remembers which line it handed out last, so
+// loaders can point their errors at it.
+#[derive(Clone, Debug)]
+pub struct Lines {
+    name: String,
+    lines: std::vec::IntoIter<String>,
+    line: usize,
+}
+
+impl Lines {
+    pub fn new(name: impl Into<String>, lines: Vec<String>) -> Lines {
+        Lines {
+            name: name.into(),
+            lines: lines.into_iter(),
+            line: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // 1-based number of the line last returned by next().
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    // Points at `bad` within `text`, the line last returned by next().
+    pub fn span(&self, text: &str, bad: &str) -> Span {
+        Span::new(text, bad).line(self.line).input(self.name.as_str())
+    }
+
+    // The lines up to the next blank line, skipping any blank lines before
+    // them, or None once the input runs out. Line numbers carry over.
+    pub fn section(&mut self) -> Option<Lines> {
+        let mut start = self.line;
+        let mut lines = Vec::new();
+        while let Some(line) = self.next() {
+            if !line.is_empty() {
+                lines.push(line);
+            } else if lines.is_empty() {
+                start = self.line;
+            } else {
+                break;
+            }
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        Some(Lines {
+            name: self.name.clone(),
+            lines: lines.into_iter(),
+            line: start,
+        })
+    }
+}
+
+impl Iterator for Lines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let line = self.lines.next()?;
+        self.line += 1;
+        Some(line)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Lines {}
+
+// Where a puzzle input comes from. Bare names like "day7" or "test_day7"
+// are looked up in the input directory; everything else is read as-is.
+// With a profile, "dayN" comes from that profile's directory instead. A
+// missing "dayN" is fetched from the puzzle server if $AOC_SESSION is set
+// and no profile is.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    Named(String),
+    Path(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl Input {
+    pub fn read(&self) -> AOCResult<String> {
+        match self {
+            Input::Named(name) => {
+                // Missing real inputs are downloaded when a session is configured.
+                let path = named_path(name);
+                if !path.exists() && profile().is_none() {
+                    if let (Some(day), Some(fetcher)) = (named_day(name), Fetcher::from_env()) {
+                        return read_file(&fetcher.fetch(day)?);
+                    }
+                }
+                read_file(&path)
+            }
+            Input::Path(path) => read_file(path),
+            Input::Stdin => {
+                let mut contents = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut contents)
+                    .map_err(|e| AOCError {
+                        e: anyhow!("Unable to read input from stdin: {}", e),
+                        span: None,
+                    })?;
+                Ok(contents)
+            }
+            Input::Text(text) => Ok(text.clone()),
+        }
+    }
+
+    pub fn lines(&self) -> AOCResult<Lines> {
+        Ok(Lines::new(
+            self.to_string(),
+            self.read()?.lines().map(|l| l.to_owned()).collect(),
+        ))
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Named(name) => write!(f, "{}", named_path(name).display()),
+            Input::Path(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "<stdin>"),
+            Input::Text(_) => write!(f, "<text>"),
+        }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(name: &str) -> Self {
+        Input::Named(name.to_owned())
+    }
+}
+
+impl From<String> for Input {
+    fn from(name: String) -> Self {
+        Input::Named(name)
+    }
+}
+
+impl From<&Input> for Input {
+    fn from(input: &Input) -> Self {
+        input.clone()
+    }
+}
+
+impl From<PathBuf> for Input {
+    fn from(path: PathBuf) -> Self {
+        Input::Path(path)
+    }
+}
+
+fn read_file(path: &Path) -> AOCResult<String> {
+    std::fs::read_to_string(path).map_err(|e| AOCError {
+        e: anyhow!("Unable to read input {}: {}", path.display(), e),
+        span: None,
+    })
+}
+
+// The --input-dir flag wins over $AOC_INPUT_DIR, which wins over "input".
+pub fn input_dir() -> PathBuf {
+    if let Some(dir) = INPUT_DIR.read().unwrap().as_ref() {
+        return dir.clone();
+    }
+    std::env::var_os(INPUT_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("input"))
+}
+
+pub fn set_input_dir(dir: impl Into<PathBuf>) {
+    *INPUT_DIR.write().unwrap() = Some(dir.into());
+}
+
+// Whose real inputs to use: the --profile flag, then $AOC_PROFILE. Each
+// profile is a directory of dayN files inside the input directory, while
+// the test_dayN examples stay shared.
+pub fn profile() -> Option<String> {
+    if let Some(profile) = PROFILE.read().unwrap().as_ref() {
+        return Some(profile.clone());
+    }
+    std::env::var(PROFILE_ENV).ok().filter(|profile| !profile.is_empty())
+}
+
+pub fn set_profile(profile: impl Into<String>) {
+    *PROFILE.write().unwrap() = Some(profile.into());
+}
+
+fn named_path(name: &str) -> PathBuf {
+    match profile() {
+        Some(profile) if named_day(name).is_some() => input_dir().join(profile).join(name),
+        _ => input_dir().join(name),
+    }
+}
+
+// The profiles in `dir`: subdirectories holding at least one dayN input.
+pub fn profiles(dir: &Path) -> AOCResult<Vec<String>> {
+    let mut profiles = Vec::new();
+    if !dir.is_dir() {
+        return Ok(profiles);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let has_input = std::fs::read_dir(&path)?
+            .filter_map(|entry| entry.ok())
+            .any(|entry| named_day(&entry.file_name().to_string_lossy()).is_some());
+        if has_input {
+            profiles.push(path.file_name().unwrap_or_default().to_string_lossy().into_owned());
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+pub fn get_input(input: impl Into<Input>) -> AOCResult<Lines> {
+    input.into().lines()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::input::*;
+
+    #[test]
+    pub fn test_input_sources() {
+        let lines = get_input(Input::Text("a\r\nb\n\nc".to_owned())).unwrap();
+        assert_eq!(lines.collect::<Vec<_>>(), vec!["a", "b", "", "c"]);
+
+        let mut sections = get_input(Input::Text("\na\nb\n\n\nc".to_owned())).unwrap();
+        let mut section = sections.section().unwrap();
+        assert_eq!(section.clone().collect::<Vec<_>>(), vec!["a", "b"]);
+        section.next();
+        assert_eq!(section.line(), 2);
+        let mut section = sections.section().unwrap();
+        section.next();
+        assert_eq!(section.span("c", "c").line, Some(6));
+        assert!(sections.section().is_none());
+
+        let mut lines = get_input("test_day8").unwrap();
+        lines.nth(2);
+        assert_eq!(lines.span("jmp +4", "+4").to_string().lines().next().unwrap(), " --> input/test_day8:3:5");
+
+        assert_eq!(get_input("test_day9").unwrap().next().unwrap(), "35");
+
+        let e = get_input("no_such_day").err().unwrap();
+        assert!(format!("{}", e).contains("no_such_day"));
+    }
+}
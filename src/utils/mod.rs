@@ -1,66 +1,109 @@
-#![allow(dead_code)]
-
 use anyhow::{anyhow, Error};
 use std::fmt;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::option::NoneError;
+use std::time::Duration;
+
+mod alloc;
+mod automaton;
+mod fetch;
+mod grid;
+mod image;
+mod input;
+mod parse;
+mod pool;
+mod rng;
+mod span;
+#[cfg(test)]
+mod test_dir;
+pub use alloc::{counting, measure, Allocs, Counting};
+pub use automaton::{moore, Automaton, Dense, Lattice, Sparse, HEX};
+pub use fetch::{base_url, session, Fetcher, SESSION_ENV, TIMEOUT, USER_AGENT};
+#[cfg(test)]
+pub use fetch::mock;
+pub use grid::{pixel, Glyph, Grid2D, ADJACENT, ORTHOGONAL};
+pub use image::{Image, Palette, Rgb};
+pub use input::{get_input, input_dir, profile, profiles, set_input_dir, set_profile, Input, Lines};
+pub use parse::{ints, parse_line, record, records, Parser};
+pub use pool::{default_jobs, map_parallel};
+pub use rng::Rng;
+pub use span::{Span, SpanExt};
+#[cfg(test)]
+pub use test_dir::{test_dir, TestDir};
 
 #[derive(Debug)]
 pub struct AOCError {
     pub e: Error,
+    // Set by loaders that know which part of the input they choked on.
+    pub span: Option<Box<Span>>,
 }
 
 pub type AOCResult<T> = Result<T, AOCError>;
 
 impl std::fmt::Display for AOCError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.e)
+        write!(f, "{}", self.e)?;
+        if let Some(span) = &self.span {
+            write!(f, "\n{}", span)?;
+        }
+        Ok(())
     }
 }
 
-// This type of implementation allows me to use '?' to
-// return early from an Optional that resolves to None in
-// a function that returns Result<T, E>.
-//
-// This special logic -- creating a new struct -- is required because
-//   1. NoneError does not implement std::error::Error and therefore can't
-//      be given straight to an anyhow::Error
-//   2. I cannot implement std's Error for NoneError -- I've seen this referred
-//      to as orphan implementation. I own neither type nor trait; it's illegal.
-//
-//
-impl From<NoneError> for AOCError {
-    fn from(_: NoneError) -> Self {
+// Any real error converts straight into an AOCError, which is what lets
+// '?' work on ParseIntError, regex::Error, io::Error and friends. This
+// blanket impl only stays coherent because AOCError itself never
+// implements std::error::Error (anyhow::Error plays the same trick).
+impl<E> From<E> for AOCError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(e: E) -> Self {
         AOCError {
-            e: anyhow!("Got None, expected Some(.)"),
+            e: Error::from(e),
+            span: None,
         }
     }
 }
 
-// Oh gosh what a nightmare. I can't implement both a generic impl for
-// From<T: std::error::Error> AND From<NoneError> because the two seem
-// to overlap! This is bizarre because NoneError doesn't implement
-// std::error::Error -- if it did, specialization should fix it -- but
-// I think it's because NoneError quacks like a std::error::Error, which
-// confuses the type system.
-//
-// To get around this, I'm just manually implementing per error as I
-// encounter them.
-//
-// I hate this so much.
-macro_rules! aocerror {
-    ($type:path) => {
-        impl From<$type> for AOCError {
-            fn from(e: $type) -> Self {
-                AOCError { e: Error::from(e) }
-            }
-        }
-    };
+// Stable Rust has no NoneError, so '?' can't early-return a None out of a
+// function returning AOCResult. `.ok_or_none()?` stands in for the old
+// bare '?' and keeps its message.
+pub trait OptionExt<T> {
+    fn ok_or_none(self) -> AOCResult<T>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn ok_or_none(self) -> AOCResult<T> {
+        self.ok_or_else(|| AOCError {
+            e: anyhow!("Got None, expected Some(.)"),
+            span: None,
+        })
+    }
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1 << 10 {
+        format!("{}B", bytes)
+    } else if bytes < 1 << 20 {
+        format!("{:.2}KiB", bytes as f64 / (1u64 << 10) as f64)
+    } else if bytes < 1 << 30 {
+        format!("{:.2}MiB", bytes as f64 / (1u64 << 20) as f64)
+    } else {
+        format!("{:.2}GiB", bytes as f64 / (1u64 << 30) as f64)
+    }
 }
-aocerror!(std::num::ParseIntError);
-aocerror!(regex::Error);
-aocerror!(strum::ParseError);
 
 //
 // WHAT DO YOU MEAN MACROS HAVE DIFFERENT EXPORT RULES?
@@ -69,12 +112,21 @@ aocerror!(strum::ParseError);
 // during a preprocessing, it doesn't make sense to have them namespaced.
 // So we have the super ugly custom bail! macro to replace the fact that
 // I can't just use anyhow::bail!()
+//
+// `aocbail!(at span; ...)` also points the error at a place in the input.
 #[macro_export]
 macro_rules! aocbail {
+    (at $span:expr; $($args:tt)*) => {
+        return Err($crate::utils::AOCError {
+            e: $crate::anyhow::anyhow!($($args)*),
+            span: Some(Box::new($span)),
+        })
+    };
     ($($args:tt)*) => {
-        return Err(AOCError {
-            e: anyhow::anyhow!($($args)*),
-        });
+        return Err($crate::utils::AOCError {
+            e: $crate::anyhow::anyhow!($($args)*),
+            span: None,
+        })
     };
 }
 
@@ -84,11 +136,3 @@ macro_rules! regex {
         regex::Regex::new($regex).unwrap()
     };
 }
-
-pub fn get_input(filename: &str) -> Box<dyn Iterator<Item = String>> {
-    Box::new(
-        BufReader::new(File::open("input/".to_owned() + filename).unwrap())
-            .lines()
-            .map(|l| l.unwrap()),
-    )
-}
@@ -0,0 +1,252 @@
+use std::str::FromStr;
+
+use super::{AOCError, AOCResult, Lines, Span, SpanExt};
+use crate::aocbail;
+
+// A cursor over one line of input. Each combinator consumes what it
+// matched from the front, or fails with an error pointing at the spot in
+// the line where it gave up.
+#[derive(Clone, Copy, Debug)]
+pub struct Parser<'a> {
+    line: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Parser { line, rest: line }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    // Points at `bad`, which should be a slice of this parser's line.
+    pub fn span(&self, bad: &str) -> Span {
+        Span::new(self.line, bad)
+    }
+
+    // What to point at when something unexpected comes next: the next
+    // token, or the end of the line.
+    fn next_token(&self) -> &'a str {
+        let end = self
+            .rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_whitespace() || c.is_ascii_punctuation())
+            .map_or(self.rest.len(), |(i, _)| i);
+        &self.rest[..end]
+    }
+
+    fn advance(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        taken
+    }
+
+    pub fn peek(&self, prefix: &str) -> bool {
+        self.rest.starts_with(prefix)
+    }
+
+    // Consumes `prefix` if it comes next.
+    pub fn eat(&mut self, prefix: &str) -> bool {
+        if self.peek(prefix) {
+            self.advance(prefix.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn literal(&mut self, expected: &str) -> AOCResult<()> {
+        if !self.eat(expected) {
+            aocbail!(at self.span(self.next_token()); "Expected '{}'", expected);
+        }
+        Ok(())
+    }
+
+    pub fn take_while(&mut self, mut f: impl FnMut(char) -> bool) -> &'a str {
+        let end = self
+            .rest
+            .char_indices()
+            .find(|(_, c)| !f(*c))
+            .map_or(self.rest.len(), |(i, _)| i);
+        self.advance(end)
+    }
+
+    pub fn char(&mut self) -> AOCResult<char> {
+        match self.rest.chars().next() {
+            Some(c) => {
+                self.advance(c.len_utf8());
+                Ok(c)
+            }
+            None => aocbail!(at self.span(self.rest); "Unexpected end of line"),
+        }
+    }
+
+    // An optionally signed run of digits.
+    pub fn int<T>(&mut self) -> AOCResult<T>
+    where
+        T: FromStr,
+        T::Err: Into<AOCError>,
+    {
+        let sign = if self.peek("-") || self.peek("+") { 1 } else { 0 };
+        let digits = self.rest[sign..].chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 {
+            aocbail!(at self.span(self.next_token()); "Expected a number");
+        }
+        let number = self.advance(sign + digits);
+        number.parse::<T>().at(|| self.span(number))
+    }
+
+    // A run of letters and digits.
+    pub fn word(&mut self) -> AOCResult<&'a str> {
+        let word = self.take_while(char::is_alphanumeric);
+        if word.is_empty() {
+            aocbail!(at self.span(self.next_token()); "Expected a word");
+        }
+        Ok(word)
+    }
+
+    // Everything up to `delimiter`, which is consumed but not returned.
+    pub fn until(&mut self, delimiter: &str) -> AOCResult<&'a str> {
+        match self.rest.find(delimiter) {
+            Some(end) => {
+                let taken = self.advance(end);
+                self.advance(delimiter.len());
+                Ok(taken)
+            }
+            None => aocbail!(at self.span(&self.rest[self.rest.len()..]); "Expected '{}'", delimiter),
+        }
+    }
+
+    // Parses everything up to `delimiter` with `f`, which must use all of it.
+    pub fn before<T>(&mut self, delimiter: &str, f: impl FnOnce(&mut Parser<'a>) -> AOCResult<T>) -> AOCResult<T> {
+        let mut part = Parser {
+            line: self.line,
+            rest: self.until(delimiter)?,
+        };
+        let value = f(&mut part)?;
+        part.end()?;
+        Ok(value)
+    }
+
+    // One or more items with `separator` between them.
+    pub fn list<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Parser<'a>) -> AOCResult<T>,
+    ) -> AOCResult<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.eat(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    pub fn end(&self) -> AOCResult<()> {
+        if !self.is_empty() {
+            aocbail!(at self.span(self.rest); "Unexpected '{}'", self.rest);
+        }
+        Ok(())
+    }
+}
+
+// Parses all of `line` with `f`.
+pub fn parse_line<'a, T>(line: &'a str, f: impl FnOnce(&mut Parser<'a>) -> AOCResult<T>) -> AOCResult<T> {
+    let mut parser = Parser::new(line);
+    let value = f(&mut parser)?;
+    parser.end()?;
+    Ok(value)
+}
+
+// A line of numbers such as "3,1,2".
+pub fn ints<T>(line: &str, separator: &str) -> AOCResult<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Into<AOCError>,
+{
+    parse_line(line, |p| p.list(separator, |p| p.int()))
+}
+
+// Splits "key<separator>value".
+pub fn record<'a>(entry: &'a str, separator: &str) -> AOCResult<(&'a str, &'a str)> {
+    match entry.split_once(separator) {
+        Some(pair) => Ok(pair),
+        None => aocbail!(at Span::new(entry, entry); "Expected 'key{}value'", separator),
+    }
+}
+
+// Whitespace separated records, as in "ecl:gry pid:860033327".
+pub fn records<'a>(text: &'a str, separator: &str) -> AOCResult<Vec<(&'a str, &'a str)>> {
+    text.split_whitespace()
+        .map(|entry| record(entry, separator).within(text, entry))
+        .collect()
+}
+
+impl Lines {
+    // Parses all of the next line with `f`, locating any error in the input.
+    pub fn parse_next<T>(&mut self, f: impl FnOnce(&mut Parser<'_>) -> AOCResult<T>) -> AOCResult<T> {
+        let line = match self.next() {
+            Some(line) => line,
+            None => aocbail!("Unexpected end of {}", self.name()),
+        };
+        parse_line(&line, f).at_line(self.line()).in_input(self.name())
+    }
+
+    // Parses every remaining line with `f`.
+    pub fn parse_each<T>(&mut self, mut f: impl FnMut(&mut Parser<'_>) -> AOCResult<T>) -> AOCResult<Vec<T>> {
+        let mut values = Vec::with_capacity(self.len());
+        while self.len() > 0 {
+            values.push(self.parse_next(&mut f)?);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::parse::*;
+
+    #[test]
+    pub fn test_parse() {
+        let (min, max, letter, password) = parse_line("1-3 a: abcde", |p| {
+            let min = p.int::<usize>()?;
+            p.literal("-")?;
+            let max = p.int::<usize>()?;
+            p.literal(" ")?;
+            let letter = p.char()?;
+            p.literal(": ")?;
+            Ok((min, max, letter, p.word()?))
+        })
+        .unwrap();
+        assert_eq!((min, max, letter, password), (1, 3, 'a', "abcde"));
+
+        let (ingredients, allergens) = parse_line("mxmxvkd kfcds (contains dairy, fish)", |p| {
+            let ingredients = p.before(" (contains ", |p| p.list(" ", Parser::word))?;
+            let allergens = p.list(", ", Parser::word)?;
+            p.literal(")")?;
+            Ok((ingredients, allergens))
+        })
+        .unwrap();
+        assert_eq!(ingredients, vec!["mxmxvkd", "kfcds"]);
+        assert_eq!(allergens, vec!["dairy", "fish"]);
+
+        assert_eq!(ints::<i32>("3,-1,+2", ",").unwrap(), vec![3, -1, 2]);
+        assert_eq!(records("ecl:gry  pid:8600", ":").unwrap(), vec![("ecl", "gry"), ("pid", "8600")]);
+        assert_eq!(records("ecl:gry hgt", ":").err().unwrap().span().unwrap().column, 9);
+
+        let e = ints::<u8>("1,300", ",").err().unwrap();
+        assert_eq!(e.span().unwrap().column, 3);
+        let e = parse_line("acc", |p| p.until(" ")).err().unwrap();
+        assert_eq!((e.span().unwrap().column, e.span().unwrap().len), (4, 0));
+
+        let mut lines = Lines::new("input", vec!["1".to_owned(), "2".to_owned(), "x".to_owned()]);
+        let e = lines.parse_each(|p| p.int::<u32>()).err().unwrap();
+        assert!(e.to_string().ends_with(" --> input:3:1\n  |\n3 | x\n  | ^"));
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+// Runs `f` over `items` on up to `jobs` worker threads and returns the
+// results in item order. Workers pull the next item as they free up, so one
+// slow item doesn't hold back the rest. An item whose job panicked (and any
+// item its worker never got to) comes back as None.
+pub fn map_parallel<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<Option<R>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+
+    std::thread::scope(|scope| {
+        let workers = (0..jobs.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    if i >= items.len() {
+                        break;
+                    }
+                    let result = f(&items[i]);
+                    results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+                })
+            })
+            .collect::<Vec<_>>();
+        // Joining by hand keeps a panicking job from tearing down the scope.
+        for worker in workers {
+            let _ = worker.join();
+        }
+    });
+
+    results.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::pool::*;
+
+    #[test]
+    pub fn test_map_parallel() {
+        let items = (0..50).collect::<Vec<u64>>();
+        let squares = map_parallel(&items, 4, |i| i * i);
+        assert_eq!(squares, items.iter().map(|i| Some(i * i)).collect::<Vec<_>>());
+
+        let results = map_parallel(&[1, 2, 3], 3, |i| if *i == 2 { panic!("boom") } else { *i });
+        assert_eq!(results[0], Some(1));
+        assert_eq!(results[1], None);
+        assert_eq!(results[2], Some(3));
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use super::{AOCError, AOCResult};
+
+// Where in an input a parse error happened. `text` is the whole offending
+// line; `column` and `len` pick out the bad part of it, counted in chars.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub input: Option<String>,
+    pub line: Option<usize>,
+    pub column: usize,
+    pub len: usize,
+    pub text: String,
+}
+
+impl Span {
+    // Points at `bad` within `text`. When `bad` is a slice of `text` its
+    // exact position is used, otherwise its first occurrence, and failing
+    // that the whole line. An empty slice marks a spot, such as the end of
+    // a line that stopped short.
+    pub fn new(text: &str, bad: &str) -> Span {
+        let start = text.as_ptr() as usize;
+        let bad_start = bad.as_ptr() as usize;
+        let offset = if bad_start >= start && bad_start + bad.len() <= start + text.len() {
+            Some(bad_start - start)
+        } else if bad.is_empty() {
+            None
+        } else {
+            text.find(bad)
+        };
+
+        match offset {
+            Some(offset) => Span {
+                input: None,
+                line: None,
+                column: text[..offset].chars().count() + 1,
+                len: bad.chars().count(),
+                text: text.to_owned(),
+            },
+            None => Span {
+                input: None,
+                line: None,
+                column: 1,
+                len: text.chars().count().max(1),
+                text: text.to_owned(),
+            },
+        }
+    }
+
+    pub fn line(mut self, line: usize) -> Span {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn input(mut self, input: impl Into<String>) -> Span {
+        self.input = Some(input.into());
+        self
+    }
+}
+
+// Renders like a compiler diagnostic:
+//
+//  --> input/day8:3:5
+//   |
+// 3 | nop +x
+//   |     ^^
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = self.line.map(|l| l.to_string()).unwrap_or_default();
+        let gutter = " ".repeat(number.len());
+        write!(f, "{}--> {}", gutter, self.input.as_deref().unwrap_or("<input>"))?;
+        if let Some(line) = self.line {
+            write!(f, ":{}:{}", line, self.column)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", number, self.text)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.len.max(1))
+        )
+    }
+}
+
+impl AOCError {
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_deref()
+    }
+
+    // Attaches a location, unless a more specific one is already there.
+    pub fn at(mut self, span: Span) -> Self {
+        if self.span.is_none() {
+            self.span = Some(Box::new(span));
+        }
+        self
+    }
+
+    // Moves a location found inside `part` out to the line `part` came
+    // from, or points at `part` if there was no location yet.
+    pub fn within(mut self, text: &str, part: &str) -> Self {
+        let outer = Span::new(text, part);
+        self.span = Some(Box::new(match self.span.take() {
+            Some(inner) => Span {
+                column: outer.column + inner.column - 1,
+                text: outer.text,
+                ..*inner
+            },
+            None => outer,
+        }));
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        if let Some(span) = self.span.as_mut() {
+            span.line.get_or_insert(line);
+        }
+        self
+    }
+
+    pub fn in_input(mut self, input: impl fmt::Display) -> Self {
+        if let Some(span) = self.span.as_mut() {
+            span.input.get_or_insert_with(|| input.to_string());
+        }
+        self
+    }
+}
+
+// The AOCError span helpers, lifted onto results so they chain before '?'.
+pub trait SpanExt<T> {
+    fn at(self, span: impl FnOnce() -> Span) -> AOCResult<T>;
+    fn within(self, text: &str, part: &str) -> AOCResult<T>;
+    fn at_line(self, line: usize) -> AOCResult<T>;
+    fn in_input(self, input: impl fmt::Display) -> AOCResult<T>;
+}
+
+impl<T, E: Into<AOCError>> SpanExt<T> for Result<T, E> {
+    fn at(self, span: impl FnOnce() -> Span) -> AOCResult<T> {
+        self.map_err(|e| e.into().at(span()))
+    }
+
+    fn within(self, text: &str, part: &str) -> AOCResult<T> {
+        self.map_err(|e| e.into().within(text, part))
+    }
+
+    fn at_line(self, line: usize) -> AOCResult<T> {
+        self.map_err(|e| e.into().at_line(line))
+    }
+
+    fn in_input(self, input: impl fmt::Display) -> AOCResult<T> {
+        self.map_err(|e| e.into().in_input(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::span::*;
+    use crate::utils::OptionExt;
+
+    #[test]
+    pub fn test_span() {
+        let line = "byr:1937 xyz:12 hgt:170cm";
+        let entry = &line[9..15];
+        let unknown: AOCResult<()> = Err(AOCError { e: anyhow::anyhow!("Unknown key"), span: None });
+        let e = unknown
+            .at(|| Span::new(entry, &entry[..3]))
+            .within(line, entry)
+            .at_line(3)
+            .in_input("input/day4")
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "Unknown key\n --> input/day4:3:10\n  |\n3 | byr:1937 xyz:12 hgt:170cm\n  |          ^^^"
+        );
+
+        let e = None::<u8>.ok_or_none().at(|| Span::new("nop +x", "x").line(12)).err().unwrap();
+        assert_eq!(e.span().unwrap().column, 6);
+        assert!(e.to_string().ends_with("\n12 | nop +x\n   |      ^"));
+    }
+}
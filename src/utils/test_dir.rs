@@ -0,0 +1,45 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+// A fresh directory under the system temp dir for one test, removed again
+// when dropped, so a panicking test doesn't leave it behind.
+pub struct TestDir {
+    path: PathBuf,
+}
+
+// Each call gets its own directory, even for the same name in one process.
+pub fn test_dir(name: &str) -> TestDir {
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("aoc2020_{}_{}_{}", name, std::process::id(), n));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    TestDir { path }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[test]
+pub fn test_test_dir() {
+    let (a, b) = (test_dir("test_dir"), test_dir("test_dir"));
+    assert_ne!(a.to_path_buf(), b.to_path_buf());
+    std::fs::write(a.join("file"), "x").unwrap();
+    let path = a.to_path_buf();
+    drop(a);
+    assert!(!path.exists());
+    assert!(b.is_dir());
+}
@@ -0,0 +1,184 @@
+use std::path::{Path, PathBuf};
+
+use crate::runner::{self, RunOptions};
+use crate::solution::Day;
+use crate::utils::{self, AOCResult, Input};
+use crate::aocbail;
+
+// The manifest lives next to the inputs it describes.
+pub const MANIFEST: &str = "answers";
+
+pub fn manifest_path() -> PathBuf {
+    utils::input_dir().join(MANIFEST)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+}
+
+// One "<day> <part> <input> <answer>" per line. Blank lines and lines
+// starting with '#' are skipped; the answer is the rest of the line.
+pub fn parse_manifest(contents: &str) -> AOCResult<Vec<Expected>> {
+    let mut manifest = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = line.splitn(4, char::is_whitespace).collect::<Vec<_>>();
+        if fields.len() != 4 {
+            aocbail!("Malformed manifest entry on line {}: '{}'", i + 1, line);
+        }
+        let part = fields[1].parse::<u8>()?;
+        if part != 1 && part != 2 {
+            aocbail!("Manifest entry on line {} has part {}, expected 1 or 2", i + 1, part);
+        }
+        manifest.push(Expected {
+            day: fields[0].parse::<u8>()?,
+            part,
+            input: fields[2].to_owned(),
+            answer: fields[3].trim().to_owned(),
+        });
+    }
+    Ok(manifest)
+}
+
+pub fn load_manifest(path: &Path) -> AOCResult<Vec<Expected>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse_manifest(&contents),
+        Err(e) => aocbail!("Unable to read answer manifest {}: {}", path.display(), e),
+    }
+}
+
+// Parses `input` once and checks every expected answer against it.
+pub fn verify_input(solution: &dyn Day, input: &str, expected: &[&Expected]) -> Vec<Outcome> {
+    let model = match solution.parse(&Input::from(input)) {
+        Ok(model) => model,
+        Err(e) => {
+            return expected
+                .iter()
+                .map(|_| Outcome::Fail(format!("parse error: {}", e)))
+                .collect()
+        }
+    };
+
+    expected
+        .iter()
+        .map(|expected| match solution.part(expected.part, model.as_ref()) {
+            Ok(answer) if answer.to_string() == expected.answer => Outcome::Pass,
+            Ok(answer) => Outcome::Fail(format!("expected {}, got {}", expected.answer, answer)),
+            Err(e) => Outcome::Fail(format!("expected {}, got error: {}", expected.answer, e)),
+        })
+        .collect()
+}
+
+// Whether the manifest answers `part` for the real input, or None when the
+// day has no such part to answer.
+pub fn covered(solution: &dyn Day, entries: &[&Expected], part: u8) -> Option<bool> {
+    if !solution.has_part(part) {
+        return None;
+    }
+    Some(entries.iter().any(|e| e.part == part && Input::from(e.input.as_str()) == solution.default_input()))
+}
+
+pub fn run(days: &[u8], options: &RunOptions, manifest: &Path) -> AOCResult<()> {
+    let manifest = load_manifest(manifest)?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for day in days {
+        let solution = runner::solution(*day)?;
+        let entries = manifest
+            .iter()
+            .filter(|e| e.day == *day && options.part(e.part))
+            .collect::<Vec<_>>();
+
+        let mut inputs = Vec::new();
+        for entry in &entries {
+            if !inputs.contains(&entry.input.as_str()) {
+                inputs.push(entry.input.as_str());
+            }
+        }
+        for input in inputs {
+            let expected = entries
+                .iter()
+                .filter(|e| e.input == input)
+                .copied()
+                .collect::<Vec<_>>();
+            for (expected, outcome) in expected.iter().zip(verify_input(solution, input, &expected)) {
+                match outcome {
+                    Outcome::Pass => {
+                        passed += 1;
+                        println!("{:>3} part {}  {:<14} pass", day, expected.part, input);
+                    }
+                    Outcome::Fail(reason) => {
+                        failed += 1;
+                        println!("{:>3} part {}  {:<14} FAIL  {}", day, expected.part, input, reason);
+                    }
+                }
+            }
+        }
+
+        // Only the real input counts as missing; examples are optional.
+        for part in options.parts() {
+            match covered(solution, &entries, part) {
+                Some(true) => {}
+                Some(false) => {
+                    missing += 1;
+                    println!("{:>3} part {}  {:<14} missing", day, part, format!("day{}", day));
+                }
+                None => println!("{:>3} part {}  {:<14} n/a", day, part, format!("day{}", day)),
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    if failed > 0 {
+        aocbail!("{} answer(s) did not match the manifest", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::verify::*;
+
+    #[test]
+    pub fn test_verify() {
+        let manifest = parse_manifest("# comment\n\n6 1 test_day6 11\n6 2 test_day6 7\n").unwrap();
+        assert_eq!(
+            manifest[0],
+            Expected { day: 6, part: 1, input: "test_day6".to_owned(), answer: "11".to_owned() }
+        );
+        assert!(parse_manifest("6 3 test_day6 11").is_err());
+
+        let solution = runner::solution(6).unwrap();
+        let outcomes = verify_input(solution, "test_day6", &manifest.iter().collect::<Vec<_>>());
+        assert_eq!(outcomes, vec![Outcome::Pass, Outcome::Fail("expected 7, got 6".to_owned())]);
+    }
+
+    #[test]
+    pub fn test_covered() {
+        let manifest = parse_manifest("6 1 day6 6443
+6 2 test_day6 6
+25 1 day25 17673381
+").unwrap();
+        let entries = manifest.iter().collect::<Vec<_>>();
+        let (day6, day25) = (runner::solution(6).unwrap(), runner::solution(25).unwrap());
+        assert_eq!(covered(day6, &entries, 1), Some(true));
+        // Examples don't cover the real input.
+        assert_eq!(covered(day6, &entries, 2), Some(false));
+        assert_eq!(covered(day25, &entries, 1), Some(true));
+        // Day 25 has no part 2 to cover.
+        assert_eq!(covered(day25, &entries, 2), None);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{Duration, SystemTime};
+
+use crate::runner;
+use crate::utils::{self, AOCResult};
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    // How often the watched files are checked for changes.
+    pub interval: Duration,
+    // Passed on to each run, as `run --timeout` would take it.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions {
+            interval: Duration::from_millis(500),
+            timeout: None,
+        }
+    }
+}
+
+fn crate_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+// The day's source file followed by its real and example inputs. Inputs are
+// listed afresh on every poll so a new test_dayN file counts as a change.
+pub fn watched_files(day: u8) -> AOCResult<Vec<PathBuf>> {
+    let dir = utils::input_dir();
+    let (real, example) = (format!("day{}", day), format!("test_day{}", day));
+    let mut inputs = Vec::new();
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(&dir)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if name == real || name == example || name.starts_with(&format!("{}_", example)) {
+                inputs.push(dir.join(name));
+            }
+        }
+    }
+    // A profile's real input lives in its own directory.
+    if let Some(profile) = utils::profile() {
+        inputs.retain(|path| path.file_name() != Some(real.as_ref()));
+        inputs.push(dir.join(profile).join(&real));
+    }
+    inputs.sort();
+    let mut files = vec![crate_dir().join("src").join(format!("day{}.rs", day))];
+    files.extend(inputs);
+    Ok(files)
+}
+
+type Stamps = BTreeMap<PathBuf, Option<SystemTime>>;
+
+fn stamps(day: u8) -> AOCResult<Stamps> {
+    Ok(watched_files(day)?
+        .into_iter()
+        .map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect())
+}
+
+// Files that appeared, disappeared or were modified between two polls.
+fn changed(before: &Stamps, after: &Stamps) -> Vec<PathBuf> {
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| before.get(*path) != after.get(*path))
+        .cloned()
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+// The answer lines of `aoc2020 run`, "<name> part <n>: <answer>", by part.
+pub fn answers(output: &str) -> BTreeMap<u8, String> {
+    let mut answers = BTreeMap::new();
+    for line in output.lines() {
+        for part in 1..=2 {
+            if let Some((_, answer)) = line.split_once(&format!(" part {}: ", part)) {
+                answers.insert(part, answer.to_owned());
+            }
+        }
+    }
+    answers
+}
+
+// Each part's answer next to what it was on the previous run.
+pub fn compare(previous: Option<&BTreeMap<u8, String>>, current: &BTreeMap<u8, String>) -> Vec<String> {
+    current
+        .iter()
+        .map(|(part, answer)| {
+            let was = match previous.map(|previous| previous.get(part)) {
+                None => String::new(),
+                Some(None) => "  (new)".to_owned(),
+                Some(Some(was)) if was == answer => "  (unchanged)".to_owned(),
+                Some(Some(was)) => format!("  (was {})", was),
+            };
+            format!("part {}: {}{}", part, answer, was)
+        })
+        .collect()
+}
+
+// Cargo has to do the running, as a changed source file means a rebuild.
+fn cargo(args: &[String]) -> AOCResult<Output> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+    Ok(Command::new(cargo).args(args).current_dir(crate_dir()).output()?)
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn print_failure(output: &Output) {
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+}
+
+// Runs the day's unit tests, then the solution in release mode, and returns
+// its answers when the solution could be run at all.
+fn rerun(day: u8, options: &WatchOptions) -> AOCResult<Option<BTreeMap<u8, String>>> {
+    let tests = cargo(&args(&["test", "--quiet", "--lib", &format!("day{}::", day)]))?;
+    if tests.status.success() {
+        let summary = String::from_utf8_lossy(&tests.stdout);
+        for line in summary.lines().filter(|line| line.starts_with("test result")) {
+            println!("tests: {}", line.trim_start_matches("test result: "));
+        }
+    } else {
+        println!("tests: FAILED");
+        print_failure(&tests);
+    }
+
+    // cargo runs from the crate, so a relative input dir is resolved here.
+    let dir = std::env::current_dir()?.join(utils::input_dir());
+    let mut run = args(&["run", "--quiet", "--release", "--", "run", &day.to_string()]);
+    run.extend(args(&["--input-dir", &dir.to_string_lossy()]));
+    if let Some(profile) = utils::profile() {
+        run.extend(args(&["--profile", &profile]));
+    }
+    if let Some(timeout) = options.timeout {
+        run.extend(args(&["--timeout", &timeout.as_secs_f64().to_string()]));
+    }
+    let run = cargo(&run)?;
+    if !run.status.success() && run.stdout.is_empty() {
+        print_failure(&run);
+        return Ok(None);
+    }
+    // Failed parts are reported on stderr, in the same form as answers.
+    let output = String::from_utf8_lossy(&run.stdout).into_owned() + &String::from_utf8_lossy(&run.stderr);
+    Ok(Some(answers(&output)))
+}
+
+fn display(path: &Path) -> String {
+    path.strip_prefix(crate_dir()).unwrap_or(path).display().to_string()
+}
+
+// Re-runs `day` whenever one of its files changes, until interrupted.
+pub fn run(day: u8, options: &WatchOptions) -> AOCResult<()> {
+    runner::solution(day)?;
+    let files = watched_files(day)?;
+    println!("watching {}", files.iter().map(|path| display(path)).collect::<Vec<_>>().join(", "));
+
+    let mut previous = None;
+    let mut seen = stamps(day)?;
+    let mut changes: Vec<PathBuf> = Vec::new();
+    loop {
+        if changes.is_empty() {
+            println!("--- day {} ---", day);
+        } else {
+            let changes = changes.iter().map(|path| display(path)).collect::<Vec<_>>();
+            println!("--- day {} ({} changed) ---", day, changes.join(", "));
+        }
+        if let Some(answers) = rerun(day, options)? {
+            for line in compare(previous.as_ref(), &answers) {
+                println!("{}", line);
+            }
+            previous = Some(answers);
+        }
+
+        loop {
+            std::thread::sleep(options.interval);
+            let now = stamps(day)?;
+            changes = changed(&seen, &now);
+            if !changes.is_empty() {
+                // Let editors that write in several steps finish first.
+                std::thread::sleep(options.interval);
+                seen = stamps(day)?;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::watch::*;
+
+    #[test]
+    pub fn test_watch() {
+        let files = watched_files(13).unwrap();
+        let names = files.iter().map(|f| f.file_name().unwrap().to_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["day13.rs", "day13", "test_day13", "test_day13_2"]);
+
+        let before = stamps(13).unwrap();
+        let mut after = before.clone();
+        let added = files[2].with_file_name("test_day13_3");
+        after.insert(files[2].clone(), Some(SystemTime::UNIX_EPOCH));
+        after.insert(added.clone(), None);
+        assert_eq!(changed(&before, &after), vec![files[2].clone(), added]);
+
+        let first = answers("--- day 13 ---\nshuttle_search part 1: 295\n");
+        let second = answers("shuttle_search part 1: 296\nshuttle_search part 2: error: boom\n");
+        assert_eq!(compare(None, &first), vec!["part 1: 295"]);
+        assert_eq!(compare(Some(&first), &second), vec!["part 1: 296  (was 295)", "part 2: error: boom  (new)"]);
+        assert_eq!(compare(Some(&second), &second)[0], "part 1: 296  (unchanged)");
+    }
+}